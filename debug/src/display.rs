@@ -0,0 +1,154 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, spanned::Spanned, Data, DeriveInput, Fields, LitStr};
+
+use crate::add_trait_bounds;
+use crate::format::placeholders;
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let format = display_attr(&input.attrs)?;
+
+    let arms = match &input.data {
+        Data::Struct(data) => {
+            let format = format.clone().ok_or_else(|| {
+                syn::Error::new(name.span(), "missing `#[display(\"...\")]` attribute")
+            })?;
+            vec![display_arm(quote!(Self), &data.fields, &format)]
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let format = display_attr(&variant.attrs)?
+                    .or_else(|| format.clone())
+                    .ok_or_else(|| {
+                        syn::Error::new(ident.span(), "missing `#[display(\"...\")]` attribute")
+                    })?;
+                Ok(display_arm(quote!(Self::#ident), &variant.fields, &format))
+            })
+            .collect::<syn::Result<_>>()?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`CustomDisplay` does not support unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    for (trait_path, field_types) in formatted_fields(&input.data, format.as_ref())? {
+        generics = add_trait_bounds(generics, field_types.into_iter(), &[], true, &trait_path);
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn fmt(&self, __formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// Reads the format string out of the `#[display("...")]` attributes, if any.
+fn display_attr(attrs: &[syn::Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut format = None;
+    for attr in attrs {
+        if !attr.path().is_ident("display") {
+            continue;
+        }
+        let lit = attr.parse_args::<LitStr>()?;
+        if format.is_some() {
+            return Err(syn::Error::new(
+                lit.span(),
+                "duplicate `#[display(\"...\")]` attribute",
+            ));
+        }
+        format = Some(lit);
+    }
+    Ok(format)
+}
+
+/// Binds every field to a local of the same name, or `_0`, `_1`, ... for tuple
+/// fields, so that the format string can refer to them by name.
+fn display_arm(path: TokenStream, fields: &Fields, format: &LitStr) -> TokenStream {
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#names),* })
+        }
+        Fields::Unnamed(fields) => {
+            let names =
+                (0..fields.unnamed.len()).map(|i| format_ident!("_{i}", span = Span::call_site()));
+            quote!(( #(#names),* ))
+        }
+        Fields::Unit => quote!(),
+    };
+    quote! {
+        #path #pattern => write!(__formatter, #format),
+    }
+}
+
+/// The types of the fields that the format strings refer to, grouped by the
+/// formatting trait their placeholders need, so that bounds are only inferred
+/// for the fields actually formatted.
+fn formatted_fields<'a>(
+    data: &'a Data,
+    default: Option<&LitStr>,
+) -> syn::Result<Vec<(syn::Path, Vec<&'a syn::Type>)>> {
+    let arms = match data {
+        Data::Struct(data) => vec![(&data.fields, default.cloned())],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let format = display_attr(&variant.attrs)?.or_else(|| default.cloned());
+                Ok((&variant.fields, format))
+            })
+            .collect::<syn::Result<_>>()?,
+        Data::Union(_) => Vec::new(),
+    };
+
+    let mut by_trait = Vec::<(syn::Path, Vec<&syn::Type>)>::new();
+    for (fields, format) in arms {
+        let Some(format) = format else {
+            continue;
+        };
+        for (arg, spec) in placeholders(&format.value()) {
+            let Some(field) = fields.iter().enumerate().find_map(|(i, field)| {
+                let name = match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => format!("_{i}"),
+                };
+                (name == arg).then_some(field)
+            }) else {
+                continue;
+            };
+            let trait_path = format_trait(&spec);
+            match by_trait.iter_mut().find(|(path, _)| *path == trait_path) {
+                Some((_, types)) => types.push(&field.ty),
+                None => by_trait.push((trait_path, vec![&field.ty])),
+            }
+        }
+    }
+    Ok(by_trait)
+}
+
+/// The trait a placeholder with this `spec` formats its argument through.
+fn format_trait(spec: &str) -> syn::Path {
+    match spec.chars().last() {
+        Some('?') => parse_quote!(std::fmt::Debug),
+        Some('x') => parse_quote!(std::fmt::LowerHex),
+        Some('X') => parse_quote!(std::fmt::UpperHex),
+        Some('o') => parse_quote!(std::fmt::Octal),
+        Some('b') => parse_quote!(std::fmt::Binary),
+        Some('e') => parse_quote!(std::fmt::LowerExp),
+        Some('E') => parse_quote!(std::fmt::UpperExp),
+        _ => parse_quote!(std::fmt::Display),
+    }
+}
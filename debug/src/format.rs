@@ -0,0 +1,112 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Expr, Ident, LitStr};
+
+/// Rewrites the `{self.path}` placeholders of a `#[debug = "..."]` format
+/// string into named arguments, and builds the `format_args!` invocation that
+/// formats `value` with it.
+///
+/// The first segment of every `self.` placeholder has to name one of `fields`,
+/// unless it is a method call. Positional placeholders keep referring to the
+/// field being formatted.
+pub(crate) fn format_args(
+    format: &LitStr,
+    value: TokenStream,
+    fields: &[&Ident],
+) -> syn::Result<TokenStream> {
+    let source = format.value();
+    let mut rewritten = String::with_capacity(source.len());
+    let mut names = Vec::new();
+    let mut exprs = Vec::new();
+    let mut positional = false;
+
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        rewritten.push(c);
+        match c {
+            '{' if chars.peek() == Some(&'{') => rewritten.extend(chars.next()),
+            '{' => {
+                let mut placeholder = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    placeholder.push(c);
+                }
+                let (arg, spec) = split_spec(&placeholder);
+                if let Some(path) = arg.strip_prefix("self.") {
+                    check_field(format, path, fields)?;
+                    let name = format_ident!("__self_{}", names.len());
+                    rewritten.push_str(&name.to_string());
+                    exprs.push(LitStr::new(arg, format.span()).parse::<Expr>()?);
+                    names.push(name);
+                } else {
+                    positional |= arg.is_empty() || arg.bytes().all(|b| b.is_ascii_digit());
+                    rewritten.push_str(arg);
+                }
+                rewritten.push_str(spec);
+                rewritten.push('}');
+            }
+            _ => {}
+        }
+    }
+
+    if names.is_empty() {
+        return Ok(quote! { format_args!(#format, #value) });
+    }
+    let rewritten = LitStr::new(&rewritten, format.span());
+    let value = positional.then(|| quote!(#value,));
+    Ok(quote! { format_args!(#rewritten, #value #(#names = #exprs),*) })
+}
+
+/// The `(argument, spec)` of every placeholder in a format string, skipping
+/// escaped braces.
+pub(crate) fn placeholders(source: &str) -> Vec<(String, String)> {
+    let mut placeholders = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+        let placeholder = chars.by_ref().take_while(|&c| c != '}').collect::<String>();
+        let (arg, spec) = split_spec(&placeholder);
+        placeholders.push((
+            arg.trim().to_owned(),
+            spec.trim_start_matches(':').to_owned(),
+        ));
+    }
+    placeholders
+}
+
+/// Splits a placeholder into its argument and its `:spec`, ignoring the `::`
+/// of paths inside the argument.
+fn split_spec(placeholder: &str) -> (&str, &str) {
+    let bytes = placeholder.as_bytes();
+    let colon = (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':')
+    });
+    match colon {
+        Some(i) => placeholder.split_at(i),
+        None => (placeholder, ""),
+    }
+}
+
+fn check_field(format: &LitStr, path: &str, fields: &[&Ident]) -> syn::Result<()> {
+    let end = path.find(['.', '(', '[', ' ']).unwrap_or(path.len());
+    if path[end..].starts_with('(') {
+        return Ok(());
+    }
+    let first = &path[..end];
+    if fields.iter().any(|field| *field == first) {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            format.span(),
+            format!("no field `{first}` to interpolate in `{{self.{path}}}`"),
+        ))
+    }
+}
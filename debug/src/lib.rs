@@ -1,61 +1,220 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_macro_input, parse_quote, Data, DeriveInput, Field, GenericParam, Generics, Ident, LitStr,
+    meta::ParseNestedMeta,
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Data, DeriveInput, Field, GenericParam, Generics, Ident, LitStr,
 };
 
+mod display;
+mod format;
+
 #[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let lit_name = LitStr::new(&name.to_string(), name.span());
 
-    let fields_debug = fields(&input.data).map(field_debug);
+    if let Err(err) = check_supported(&input.data) {
+        return err.to_compile_error().into();
+    }
+    let container = match outer_debug_attr(&input.attrs) {
+        Ok(container) => container,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {#err}.into();
+        }
+    };
+    let lit_name = container
+        .rename
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&name.to_string(), name.span()));
 
-    let bound = match input
-        .attrs
-        .iter()
-        .map(outer_debug_attr)
+    let field_attrs = match fields(&input.data)
+        .map(debug_attr)
         .collect::<syn::Result<Vec<_>>>()
     {
-        Ok(predicates) => predicates.into_iter().fold(None, |a, b| a.or(b)),
+        Ok(field_attrs) => field_attrs,
         Err(err) => {
             let err = err.to_compile_error();
             return quote! {#err}.into();
         }
     };
+    let non_exhaustive = field_attrs.iter().any(|attrs| attrs.skip.is_some());
+    let debug_with = field_attrs
+        .iter()
+        .any(|attrs| attrs.with.is_some())
+        .then(debug_with_wrapper);
+    let debug_truncated = field_attrs
+        .iter()
+        .any(|attrs| attrs.truncate.is_some())
+        .then(debug_truncated_wrapper);
+    let body = match container.transparent {
+        Some(span) => match transparent_debug(&input.data, span, &field_attrs) {
+            Ok(value) => quote! {
+                #debug_with
+                #debug_truncated
+                std::fmt::Debug::fmt(#value, f)
+            },
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => {
+            let field_names = fields(&input.data)
+                .filter_map(|field| field.ident.as_ref())
+                .collect::<Vec<_>>();
+            let mut fields_debug = match fields(&input.data)
+                .zip(&field_attrs)
+                .map(|(field, attrs)| field_debug(field, attrs, &field_names))
+                .collect::<syn::Result<Vec<_>>>()
+            {
+                Ok(fields_debug) => fields_debug.into_iter().flatten().collect::<Vec<_>>(),
+                Err(err) => return err.to_compile_error().into(),
+            };
+            if container.sort_fields.is_some() {
+                fields_debug.sort_by_key(|(lit_name, _)| lit_name.value());
+            }
+            let fmt_fields = if container.compact.is_some() {
+                compact_debug(&lit_name, &fields_debug, non_exhaustive)
+            } else {
+                struct_debug(&lit_name, &fields_debug, non_exhaustive)
+            };
+            quote! {
+                #debug_with
+                #debug_truncated
+                #fmt_fields
+            }
+        }
+    };
 
-    let generics = add_trait_bounds(input.generics, &input.data, bound.as_ref());
+    let generics = add_debug_bounds(input.generics, &input.data, &container, &field_attrs);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     quote! {
         impl #impl_generics std::fmt::Debug for #name #ty_generics #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_struct(#lit_name)
-                    #(#fields_debug)*
-                    .finish()
+                #body
             }
         }
     }
     .into()
 }
 
-fn debug_attr(field: &Field) -> Option<LitStr> {
-    let attr = field.attrs.first()?;
-    if !attr.path().is_ident("debug") {
-        return None;
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    display::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    format: Option<LitStr>,
+    rename: Option<LitStr>,
+    skip: Option<Span>,
+    redact: Option<LitStr>,
+    with: Option<syn::ExprPath>,
+    bounds: Option<Vec<DebugBound>>,
+    hex: Option<Span>,
+    bin: Option<Span>,
+    truncate: Option<syn::LitInt>,
+    expr: Option<syn::Expr>,
+    wrapper: Option<syn::Path>,
+}
+
+impl FieldAttrs {
+    fn check_conflicts(&self) -> syn::Result<()> {
+        check_conflicts([
+            self.format
+                .as_ref()
+                .map(|lit| ("debug = \"...\"", lit.span())),
+            self.skip.map(|span| ("skip", span)),
+            self.redact.as_ref().map(|lit| ("redact", lit.span())),
+            self.with.as_ref().map(|path| ("with", path.span())),
+            self.hex.map(|span| ("hex", span)),
+            self.bin.map(|span| ("bin", span)),
+            self.truncate.as_ref().map(|lit| ("truncate", lit.span())),
+            self.expr.as_ref().map(|expr| ("expr", expr.span())),
+            self.wrapper.as_ref().map(|path| ("as", path.span())),
+        ])
     }
-    let syn::Meta::NameValue(meta) = &attr.meta else {
-        return None;
-    };
-    let syn::Expr::Lit(expr) = &meta.value else {
-        return None;
-    };
-    if let syn::Lit::Str(lit_str) = &expr.lit {
-        Some(lit_str.clone())
-    } else {
-        None
+}
+
+fn debug_attr(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(meta) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                }) = &meta.value
+                else {
+                    return Err(syn::Error::new(
+                        meta.value.span(),
+                        "expected a format string literal",
+                    ));
+                };
+                if attrs.format.is_some() {
+                    return Err(syn::Error::new(
+                        lit_str.span(),
+                        "duplicate `debug = \"...\"` format",
+                    ));
+                }
+                attrs.format = Some(lit_str.clone());
+            }
+            syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    set_once(&mut attrs.skip, meta.path.span(), &meta)
+                } else if meta.path.is_ident("rename") {
+                    set_once(&mut attrs.rename, meta.value()?.parse()?, &meta)
+                } else if meta.path.is_ident("redact") {
+                    let placeholder = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse()?
+                    } else {
+                        LitStr::new("***", meta.path.span())
+                    };
+                    set_once(&mut attrs.redact, placeholder, &meta)
+                } else if meta.path.is_ident("with") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    set_once(&mut attrs.with, lit.parse()?, &meta)
+                } else if meta.path.is_ident("bound") {
+                    set_once(&mut attrs.bounds, parse_bounds(&meta)?, &meta)
+                } else if meta.path.is_ident("hex") {
+                    set_once(&mut attrs.hex, meta.path.span(), &meta)
+                } else if meta.path.is_ident("bin") {
+                    set_once(&mut attrs.bin, meta.path.span(), &meta)
+                } else if meta.path.is_ident("truncate") {
+                    let lit = meta.value()?.parse::<syn::LitInt>()?;
+                    lit.base10_parse::<usize>()?;
+                    set_once(&mut attrs.truncate, lit, &meta)
+                } else if meta.path.is_ident("expr") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    set_once(&mut attrs.expr, lit.parse()?, &meta)
+                } else if meta.path.is_ident("as") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    set_once(&mut attrs.wrapper, lit.parse()?, &meta)
+                } else {
+                    Err(meta.error(
+                        "expected one of `skip`, `redact`, `rename`, `with`, `bound`, `hex`, `bin`, `truncate`, `expr` or `as`",
+                    ))
+                }
+            })?,
+            syn::Meta::Path(path) => {
+                return Err(syn::Error::new(
+                    path.span(),
+                    "expected `#[debug = \"...\"]` or `#[debug(...)]`",
+                ));
+            }
+        }
     }
+    attrs.check_conflicts()?;
+    Ok(attrs)
 }
 
 struct DebugBound {
@@ -63,122 +222,487 @@ struct DebugBound {
     predicate: syn::WherePredicate,
 }
 
-fn outer_debug_attr(attr: &syn::Attribute) -> syn::Result<Option<DebugBound>> {
-    if !attr.path().is_ident("debug") {
-        return Ok(None);
+#[derive(Default)]
+struct ContainerAttrs {
+    bounds: Vec<DebugBound>,
+    infer_bounds: bool,
+    rename: Option<LitStr>,
+    transparent: Option<Span>,
+    compact: Option<Span>,
+    sort_fields: Option<Span>,
+}
+
+impl ContainerAttrs {
+    fn check_conflicts(&self) -> syn::Result<()> {
+        for (option, span) in [
+            self.rename.as_ref().map(|lit| ("rename", lit.span())),
+            self.compact.map(|span| ("compact", span)),
+            self.sort_fields.map(|span| ("sort_fields", span)),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            check_conflicts([
+                self.transparent.map(|span| ("transparent", span)),
+                Some((option, span)),
+            ])?;
+        }
+        Ok(())
     }
-    let mut bound = None;
-    attr.parse_nested_meta(|meta| {
-        if !meta.path.is_ident("bound") {
-            return Ok(());
-        }
-        let value = meta.value()?;
-        let lit = value.parse::<LitStr>()?;
-        let predicate = syn::parse_str::<syn::WherePredicate>(&lit.value())?;
-        let generic = match &predicate {
-            syn::WherePredicate::Type(p_ty) => match &p_ty.bounded_ty {
-                syn::Type::Path(syn::TypePath { path, .. }) => {
-                    if path.segments.len() > 1 {
-                        let segment = path.segments.first().unwrap();
-                        Some(segment.ident.clone())
-                    } else {
-                        None
+}
+
+fn outer_debug_attr(attrs: &[syn::Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut container = ContainerAttrs {
+        infer_bounds: true,
+        ..ContainerAttrs::default()
+    };
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                return set_once(&mut container.rename, meta.value()?.parse()?, &meta);
+            }
+            if meta.path.is_ident("compact") {
+                return set_once(&mut container.compact, meta.path.span(), &meta);
+            }
+            if meta.path.is_ident("sort_fields") {
+                return set_once(&mut container.sort_fields, meta.path.span(), &meta);
+            }
+            if meta.path.is_ident("transparent") {
+                return set_once(&mut container.transparent, meta.path.span(), &meta);
+            }
+            if !meta.path.is_ident("bound") {
+                return Err(meta.error(
+                    "expected one of `bound`, `rename`, `transparent`, `compact` or `sort_fields`",
+                ));
+            }
+            let bounds = parse_bounds(&meta)?;
+            if bounds.is_empty() {
+                container.infer_bounds = false;
+            }
+            container.bounds.extend(bounds);
+            Ok(())
+        })?;
+    }
+    container.check_conflicts()?;
+    Ok(container)
+}
+
+fn bounds_debug(bounds: &Punctuated<syn::TypeParamBound, syn::Token![+]>) -> bool {
+    bounds.iter().any(|bound| match bound {
+        syn::TypeParamBound::Trait(bound) => bound
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Debug"),
+        _ => false,
+    })
+}
+
+fn parse_bounds(meta: &ParseNestedMeta) -> syn::Result<Vec<DebugBound>> {
+    let lit = meta.value()?.parse::<LitStr>()?;
+    let predicates =
+        lit.parse_with(Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated)?;
+    Ok(predicates
+        .into_iter()
+        .map(|predicate| {
+            // `T::Value: Debug` and `T: Debug` stand in for the inferred
+            // `T: Debug`, while an unrelated `U: Clone` leaves it alone.
+            let generic = match &predicate {
+                syn::WherePredicate::Type(p_ty) => match &p_ty.bounded_ty {
+                    syn::Type::Path(syn::TypePath { path, .. })
+                        if path.segments.len() > 1 || bounds_debug(&p_ty.bounds) =>
+                    {
+                        path.segments.first().map(|segment| segment.ident.clone())
                     }
-                }
+                    _ => None,
+                },
                 _ => None,
-            },
-            _ => None,
-        };
-        bound = Some(DebugBound { generic, predicate });
-        Ok(())
-    })?;
-    Ok(bound)
+            };
+            DebugBound { generic, predicate }
+        })
+        .collect())
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if slot.is_some() {
+        let path = &meta.path;
+        return Err(meta.error(format!("duplicate `debug({})` option", quote!(#path))));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn check_conflicts<const N: usize>(options: [Option<(&str, Span)>; N]) -> syn::Result<()> {
+    let mut options = options.into_iter().flatten();
+    if let (Some((first, _)), Some((second, span))) = (options.next(), options.next()) {
+        return Err(syn::Error::new(
+            span,
+            format!("`{second}` cannot be combined with `{first}`"),
+        ));
+    }
+    Ok(())
+}
+
+/// Formats the only field of a `debug(transparent)` struct, honoring its own
+/// `debug(...)` options.
+fn transparent_debug(
+    data: &Data,
+    span: Span,
+    field_attrs: &[FieldAttrs],
+) -> syn::Result<TokenStream> {
+    let member = transparent_member(data, span)?;
+    let attrs = &field_attrs[0];
+    let field_names = fields(data)
+        .filter_map(|field| field.ident.as_ref())
+        .collect::<Vec<_>>();
+    field_value(&member, attrs, &field_names)?.ok_or_else(|| {
+        syn::Error::new(
+            attrs.skip.unwrap_or(span),
+            "cannot skip the only field of a `debug(transparent)` struct",
+        )
+    })
+}
+
+fn transparent_member(data: &Data, span: Span) -> syn::Result<syn::Member> {
+    let Data::Struct(data) = data else {
+        return Err(syn::Error::new(
+            span,
+            "`debug(transparent)` is only supported on structs",
+        ));
+    };
+    let mut fields = data.fields.iter();
+    match (fields.next(), fields.next()) {
+        (Some(field), None) => Ok(match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(0.into()),
+        }),
+        _ => Err(syn::Error::new(
+            span,
+            "`debug(transparent)` requires a struct with exactly one field",
+        )),
+    }
 }
 
 fn fields(data: &Data) -> impl Iterator<Item = &Field> {
     match data {
-        Data::Struct(data) => match &data.fields {
-            syn::Fields::Named(fields) => fields.named.iter(),
-            _ => unimplemented!(),
-        },
-        _ => unimplemented!(),
+        Data::Struct(data) => Some(data.fields.iter()),
+        _ => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
+fn check_supported(data: &Data) -> syn::Result<()> {
+    match data {
+        Data::Struct(_) => Ok(()),
+        Data::Enum(data) => Err(syn::Error::new(
+            data.enum_token.span,
+            "`CustomDebug` does not support enums",
+        )),
+        Data::Union(data) => Err(syn::Error::new(
+            data.union_token.span,
+            "`CustomDebug` does not support unions",
+        )),
     }
 }
 
-fn field_debug(field: &Field) -> TokenStream {
+fn field_debug(
+    field: &Field,
+    attrs: &FieldAttrs,
+    field_names: &[&Ident],
+) -> syn::Result<Option<(LitStr, TokenStream)>> {
     let Some(name) = &field.ident else {
-        unimplemented!();
+        return Err(syn::Error::new(
+            field.span(),
+            "`CustomDebug` only supports tuple structs with `#[debug(transparent)]`",
+        ));
+    };
+    let lit_name = attrs
+        .rename
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&name.to_string(), name.span()));
+    let member = syn::Member::Named(name.clone());
+    Ok(field_value(&member, attrs, field_names)?.map(|value| (lit_name, value)))
+}
+
+/// The expression a field is formatted through, or `None` if it is skipped.
+fn field_value(
+    name: &syn::Member,
+    attrs: &FieldAttrs,
+    field_names: &[&Ident],
+) -> syn::Result<Option<TokenStream>> {
+    let value = if attrs.skip.is_some() {
+        return Ok(None);
+    } else if let Some(placeholder) = &attrs.redact {
+        quote! { &format_args!("{}", #placeholder) }
+    } else if let Some(with) = &attrs.with {
+        quote! { &DebugWith(&self.#name, #with) }
+    } else if let Some(expr) = &attrs.expr {
+        quote! { &(#expr) }
+    } else if let Some(wrapper) = &attrs.wrapper {
+        quote! { &#wrapper(&self.#name) }
+    } else if attrs.hex.is_some() {
+        quote! { &format_args!("{:#x}", &self.#name) }
+    } else if attrs.bin.is_some() {
+        quote! { &format_args!("{:#b}", &self.#name) }
+    } else if let Some(max) = &attrs.truncate {
+        quote! { &DebugTruncated(&self.#name[..], #max) }
+    } else if let Some(debug) = &attrs.format {
+        let format_args = format::format_args(debug, quote!(&self.#name), field_names)?;
+        quote! { &#format_args }
+    } else {
+        quote! { &self.#name }
     };
-    let lit_name = LitStr::new(&name.to_string(), name.span());
-    match debug_attr(field) {
-        Some(debug) => quote! { .field(#lit_name, &format_args!(#debug, &self.#name)) },
-        None => quote! { .field(#lit_name, &self.#name) },
+    Ok(Some(value))
+}
+
+fn struct_debug(
+    lit_name: &LitStr,
+    fields_debug: &[(LitStr, TokenStream)],
+    non_exhaustive: bool,
+) -> TokenStream {
+    let names = fields_debug.iter().map(|(name, _)| name);
+    let values = fields_debug.iter().map(|(_, value)| value);
+    let finish = if non_exhaustive {
+        quote!(finish_non_exhaustive)
+    } else {
+        quote!(finish)
+    };
+    quote! {
+        f.debug_struct(#lit_name)
+            #(.field(#names, #values))*
+            .#finish()
+    }
+}
+
+fn compact_debug(
+    lit_name: &LitStr,
+    fields_debug: &[(LitStr, TokenStream)],
+    non_exhaustive: bool,
+) -> TokenStream {
+    let writes = fields_debug.iter().enumerate().map(|(i, (name, value))| {
+        let separator = if i == 0 { "" } else { ", " };
+        quote! { write!(f, "{}{}={:?}", #separator, #name, #value)?; }
+    });
+    let rest = non_exhaustive.then(|| {
+        let rest = if fields_debug.is_empty() {
+            ".."
+        } else {
+            ", .."
+        };
+        quote! { f.write_str(#rest)?; }
+    });
+    quote! {
+        f.write_str(#lit_name)?;
+        f.write_str("(")?;
+        #(#writes)*
+        #rest
+        f.write_str(")")
+    }
+}
+
+fn debug_with_wrapper() -> TokenStream {
+    quote! {
+        struct DebugWith<'a, T: ?Sized>(
+            &'a T,
+            fn(&T, &mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+        );
+
+        impl<T: ?Sized> std::fmt::Debug for DebugWith<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                (self.1)(self.0, f)
+            }
+        }
+    }
+}
+
+/// Formats at most `self.1` elements of a slice, or characters of a string,
+/// followed by an ellipsis and the total length.
+fn debug_truncated_wrapper() -> TokenStream {
+    quote! {
+        struct DebugTruncated<'a, T: ?Sized>(&'a T, usize);
+
+        impl std::fmt::Debug for DebugTruncated<'_, str> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let DebugTruncated(string, max) = *self;
+                match string.char_indices().nth(max) {
+                    Some((end, _)) => {
+                        write!(f, "{:?}... ({} bytes)", &string[..end], string.len())
+                    }
+                    None => std::fmt::Debug::fmt(string, f),
+                }
+            }
+        }
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for DebugTruncated<'_, [T]> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let DebugTruncated(slice, max) = *self;
+                if slice.len() <= max {
+                    return std::fmt::Debug::fmt(slice, f);
+                }
+                f.write_str("[")?;
+                for item in &slice[..max] {
+                    write!(f, "{:?}, ", item)?;
+                }
+                write!(f, "...] ({} items)", slice.len())
+            }
+        }
     }
 }
 
-fn add_trait_bounds(mut generics: Generics, data: &Data, bound: Option<&DebugBound>) -> Generics {
+fn add_debug_bounds(
+    generics: Generics,
+    data: &Data,
+    container: &ContainerAttrs,
+    field_attrs: &[FieldAttrs],
+) -> Generics {
+    let field_bounds = field_attrs
+        .iter()
+        .filter_map(|attrs| attrs.bounds.as_ref())
+        .flatten();
+    let bounds = container.bounds.iter().collect::<Vec<_>>();
+    // Fields with their own `debug(bound = "...")`, or which are not formatted
+    // through their `Debug` impl, take no part in inference. `hex` and `bin`
+    // fields need `LowerHex` and `Binary` instead.
+    let inferred = fields(data)
+        .zip(field_attrs)
+        .filter(|(_, attrs)| {
+            attrs.bounds.is_none()
+                && attrs.skip.is_none()
+                && attrs.redact.is_none()
+                && attrs.with.is_none()
+                && attrs.expr.is_none()
+                && attrs.wrapper.is_none()
+        })
+        .collect::<Vec<_>>();
+    let field_types = inferred
+        .iter()
+        .filter(|(_, attrs)| attrs.hex.is_none() && attrs.bin.is_none())
+        .map(|(field, _)| &field.ty);
+    let hex_types = inferred
+        .iter()
+        .filter(|(_, attrs)| attrs.hex.is_some())
+        .map(|(field, _)| &field.ty);
+    let bin_types = inferred
+        .iter()
+        .filter(|(_, attrs)| attrs.bin.is_some())
+        .map(|(field, _)| &field.ty);
+    let mut generics = add_trait_bounds(
+        generics,
+        field_types,
+        &bounds,
+        container.infer_bounds,
+        &parse_quote!(std::fmt::Debug),
+    );
+    generics = add_trait_bounds(
+        generics,
+        hex_types,
+        &bounds,
+        container.infer_bounds,
+        &parse_quote!(std::fmt::LowerHex),
+    );
+    generics = add_trait_bounds(
+        generics,
+        bin_types,
+        &bounds,
+        container.infer_bounds,
+        &parse_quote!(std::fmt::Binary),
+    );
+    // Field bounds stand in for inference on their own field only, so unlike
+    // container bounds they do not keep a parameter from being bounded.
+    let where_clause = generics.make_where_clause();
+    for DebugBound { predicate, .. } in field_bounds {
+        where_clause.predicates.push(predicate.clone());
+    }
+    generics
+}
+
+fn add_trait_bounds<'a>(
+    mut generics: Generics,
+    field_types: impl Iterator<Item = &'a syn::Type>,
+    bounds: &[&DebugBound],
+    infer_bounds: bool,
+    trait_path: &syn::Path,
+) -> Generics {
+    let mut visitor = TypeParamVisitor {
+        params: generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect(),
+        used: Vec::new(),
+        associated: Vec::new(),
+    };
+    field_types.for_each(|ty| visitor.visit_type(ty));
+
     generics.make_where_clause();
     if let Some(where_clause) = generics.where_clause.as_mut() {
-        if let Some(DebugBound { predicate, .. }) = bound {
+        for DebugBound { predicate, .. } in bounds {
             where_clause.predicates.push(predicate.clone());
         }
+        if !infer_bounds {
+            return generics;
+        }
         for param in &mut generics.params {
             if let GenericParam::Type(type_param) = param {
-                let phantom_data = fields(data).any(is_phantom_data_ty(&type_param.ident));
-                let associated_types = fields(data)
-                    .filter_map(get_associated_ty(&type_param.ident))
-                    .collect::<Vec<_>>();
-                let bound_attr = bound
-                    .is_some_and(|db| db.generic.as_ref().is_some_and(|g| *g == type_param.ident));
-                if !phantom_data && associated_types.is_empty() && !bound_attr {
-                    type_param.bounds.push(parse_quote!(std::fmt::Debug));
-                } else {
-                    associated_types.iter().for_each(|ty| {
-                        where_clause
-                            .predicates
-                            .push(parse_quote!(#ty: std::fmt::Debug));
-                    });
+                let bound_attr = bounds
+                    .iter()
+                    .any(|db| db.generic.as_ref().is_some_and(|g| *g == type_param.ident));
+                if visitor.used.contains(&type_param.ident) && !bound_attr {
+                    type_param.bounds.push(parse_quote!(#trait_path));
                 }
             }
         }
+        for ty in &visitor.associated {
+            where_clause.predicates.push(parse_quote!(#ty: #trait_path));
+        }
     }
     generics
 }
 
-fn is_phantom_data_ty<'a>(generic_ty: &'a Ident) -> impl Fn(&'a Field) -> bool {
-    move |field| {
-        if let Some(syn::Type::Path(ty)) = unwrap_t(Wrapper::PhantomData, field) {
-            return ty.path.get_ident().is_some_and(|ty| ty == generic_ty);
-        }
-        false
-    }
+/// Collects the type parameters, and the associated types of type parameters,
+/// whose formatting impls are needed to format a field type.
+struct TypeParamVisitor {
+    params: Vec<Ident>,
+    used: Vec<Ident>,
+    associated: Vec<syn::TypePath>,
 }
 
-fn get_associated_ty<'a>(generic_ty: &'a Ident) -> impl Fn(&'a Field) -> Option<&'a syn::Type> {
-    move |field| {
-        let syn::Type::Path(ty) = &field.ty else {
-            return None;
-        };
-        let segment = ty.path.segments.first()?;
-        let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
-            args, ..
-        }) = &segment.arguments
-        else {
-            return None;
-        };
-        let syn::GenericArgument::Type(ty) = args.first()? else {
-            return None;
-        };
-        let syn::Type::Path(syn::TypePath { path, .. }) = ty else {
-            return None;
+impl<'ast> Visit<'ast> for TypeParamVisitor {
+    fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+        let root = match &ty.qself {
+            Some(qself) => match &*qself.ty {
+                syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident(),
+                _ => None,
+            },
+            None => ty.path.segments.first().map(|segment| &segment.ident),
         };
-        let segment = path.segments.first()?;
-        if segment.ident == *generic_ty && path.segments.len() > 1 {
-            Some(ty)
-        } else {
-            None
+        if let Some(root) = root.filter(|root| self.params.contains(root)) {
+            if ty.qself.is_none() && ty.path.segments.len() == 1 {
+                if !self.used.contains(root) {
+                    self.used.push(root.clone());
+                }
+            } else if !self.associated.contains(ty) {
+                self.associated.push(ty.clone());
+            }
+            return;
         }
+        // `PhantomData<T>` is `Debug` regardless of `T`.
+        if ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == Wrapper::PhantomData.to_string())
+        {
+            return;
+        }
+        visit::visit_type_path(self, ty);
     }
+
+    // Function pointers are `Debug` regardless of their signature.
+    fn visit_type_bare_fn(&mut self, _: &'ast syn::TypeBareFn) {}
 }
 
 enum Wrapper {
@@ -193,23 +717,3 @@ impl std::fmt::Display for Wrapper {
         write!(f, "{string}")
     }
 }
-
-fn unwrap_t(wrapper: Wrapper, field: &Field) -> Option<&syn::Type> {
-    let syn::Type::Path(ty) = &field.ty else {
-        return None;
-    };
-    let segment = ty.path.segments.last()?;
-    if segment.ident != wrapper.to_string() {
-        return None;
-    };
-    let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments { args, .. }) =
-        &segment.arguments
-    else {
-        return None;
-    };
-    if let Some(syn::GenericArgument::Type(ty)) = args.first() {
-        Some(ty)
-    } else {
-        None
-    }
-}
@@ -0,0 +1,39 @@
+// Fields holding secrets can be left out of the Debug output entirely with
+// `#[debug(skip)]`, in which case the struct is printed as non-exhaustive, or
+// replaced by a placeholder with `#[debug(redact)]`. A field can also be
+// formatted by an arbitrary function through `#[debug(with = "path")]`.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(redact)]
+    password: &'static str,
+    #[debug(redact = "<hidden>")]
+    token: u64,
+    #[debug(skip)]
+    salt: [u8; 4],
+    #[debug(with = "fmt_len")]
+    history: Vec<&'static str>,
+}
+
+fn fmt_len(value: &Vec<&'static str>, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "[{} entries]", value.len())
+}
+
+fn main() {
+    let credentials = Credentials {
+        user: "admin",
+        password: "hunter2",
+        token: 0xdead_beef,
+        salt: [1, 2, 3, 4],
+        history: vec!["a", "b"],
+    };
+
+    let debug = format!("{:?}", credentials);
+    let expected = r#"Credentials { user: "admin", password: ***, token: <hidden>, history: [2 entries], .. }"#;
+
+    assert_eq!(debug, expected);
+}
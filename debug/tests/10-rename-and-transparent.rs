@@ -0,0 +1,59 @@
+// The names printed for the struct and its fields default to their Rust
+// identifiers, and can be overridden with `#[debug(rename = "...")]` on either.
+//
+// Newtypes marked `#[debug(transparent)]` print exactly like the single field
+// they wrap, without the surrounding struct name. Options on that field, such
+// as `redact` or `hex`, still apply.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "User")]
+pub struct UserRecord {
+    #[debug(rename = "id")]
+    user_id: UserId,
+    name: Name,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Name {
+    inner: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Secret(#[debug(redact)] String);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Register {
+    #[debug(hex)]
+    bits: u32,
+}
+
+fn main() {
+    let user = UserRecord {
+        user_id: UserId(42),
+        name: Name {
+            inner: "Ferris".to_owned(),
+        },
+    };
+
+    let debug = format!("{:?}", user);
+    let expected = r#"User { id: 42, name: "Ferris" }"#;
+    assert_eq!(debug, expected);
+
+    let debug = format!("{:#?}", UserId(7));
+    assert_eq!(debug, "7");
+
+    let debug = format!("{:?}", Secret("hunter2".to_owned()));
+    assert_eq!(debug, "***");
+
+    let debug = format!("{:?}", Register { bits: 255 });
+    assert_eq!(debug, "0xff");
+}
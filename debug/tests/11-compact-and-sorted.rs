@@ -0,0 +1,55 @@
+// `#[debug(compact)]` prints the struct on a single line as `Name(a=1, b=2)`,
+// even when formatted with `{:#?}`.
+//
+// `#[debug(sort_fields)]` prints fields in alphabetical order rather than in
+// declaration order, so that reordering the struct does not change its output.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Point {
+    x: i32,
+    y: i32,
+    #[debug(skip)]
+    cache: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(sort_fields)]
+pub struct Config {
+    verbose: bool,
+    #[debug(rename = "b_level")]
+    level: u8,
+    address: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact, sort_fields)]
+pub struct Pair {
+    second: Point,
+    first: &'static str,
+}
+
+fn main() {
+    let point = Point { x: 1, y: -2, cache: 0 };
+    assert_eq!(format!("{:?}", point), "Point(x=1, y=-2, ..)");
+    assert_eq!(format!("{:#?}", point), "Point(x=1, y=-2, ..)");
+
+    let config = Config {
+        verbose: true,
+        level: 3,
+        address: "localhost",
+    };
+    let debug = format!("{:?}", config);
+    let expected = r#"Config { address: "localhost", b_level: 3, verbose: true }"#;
+    assert_eq!(debug, expected);
+
+    let pair = Pair {
+        second: point,
+        first: "a",
+    };
+    let debug = format!("{:#?}", pair);
+    let expected = r#"Pair(first="a", second=Point(x=1, y=-2, ..))"#;
+    assert_eq!(debug, expected);
+}
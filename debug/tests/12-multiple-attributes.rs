@@ -0,0 +1,51 @@
+// Every `debug` attribute on a field or on the container is taken into
+// account, regardless of which other attributes come before it, and the
+// options from all of them are combined.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+    type Other;
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug")]
+#[debug(bound = "T::Other: Debug")]
+pub struct Wrapper<T: Trait> {
+    value: Vec<T::Value>,
+    other: Option<T::Other>,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename = "Flags")]
+#[debug(compact)]
+pub struct Field {
+    /// Bits that are set on the field.
+    #[allow(dead_code)]
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(rename = "token")]
+    #[debug(redact)]
+    secret: u32,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+        type Other = u16;
+    }
+
+    assert_debug::<Wrapper<Id>>();
+
+    let field = Field {
+        bitmask: 0b00011100,
+        secret: 42,
+    };
+    assert_eq!(format!("{:?}", field), "Flags(bitmask=0b00011100, token=***)");
+}
@@ -0,0 +1,25 @@
+// Options that are given twice, or that cannot be used together, are reported
+// as errors pointing at the offending option.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Duplicate {
+    #[debug(rename = "a")]
+    #[debug(rename = "b")]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Conflict {
+    #[debug = "{:x}"]
+    #[debug(redact)]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+#[debug(compact)]
+pub struct Newtype(u8);
+
+fn main() {}
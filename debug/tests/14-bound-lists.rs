@@ -0,0 +1,97 @@
+// A `debug(bound = "...")` attribute accepts a comma-separated list of where
+// predicates. An empty `bound = ""` on the container disables bound inference
+// altogether, leaving only the bounds that were written explicitly.
+//
+// Bounds can also be given on individual fields. They replace the bounds that
+// would otherwise be inferred from that field's type, and are merged with the
+// container-level bounds.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+    type Other;
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug, T::Other: Debug")]
+pub struct Wrapper<T: Trait> {
+    value: Field<T>,
+    other: Vec<T::Other>,
+}
+
+#[derive(CustomDebug)]
+struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "")]
+pub struct Handle<T> {
+    id: Id<T>,
+}
+
+pub struct Id<T>(u32, PhantomData<T>);
+
+impl<T> Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Mixed<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+// The field bound replaces inference for `values` alone, so `other` still
+// gets its inferred `T: Debug`.
+#[derive(CustomDebug)]
+pub struct Shared<T: Trait> {
+    #[debug(bound = "T::Value: Debug")]
+    values: Vec<T::Value>,
+    other: T,
+}
+
+#[derive(Debug)]
+pub struct Plain;
+
+impl Trait for Plain {
+    type Value = u8;
+    type Other = u8;
+}
+
+// A plain `U: Clone` is added as written, and `U` still gets its inferred
+// `U: Debug`.
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug, U: Clone")]
+pub struct Combined<T: Trait, U> {
+    values: Vec<T::Value>,
+    u: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    impl Trait for NotDebug {
+        type Value = u8;
+        type Other = u16;
+    }
+
+    assert_debug::<Wrapper<NotDebug>>();
+    assert_debug::<Handle<NotDebug>>();
+    assert_debug::<Mixed<NotDebug, u8>>();
+    assert_debug::<Shared<Plain>>();
+    assert_debug::<Combined<NotDebug, u8>>();
+
+    let handle = Handle::<NotDebug> {
+        id: Id(7, PhantomData),
+    };
+    assert_eq!(format!("{:?}", handle), "Handle { id: #7 }");
+}
@@ -0,0 +1,41 @@
+// Trait bounds are inferred from every place a type parameter appears inside
+// the field types, including tuples, arrays, references, nested generic
+// arguments and associated types in any argument position.
+//
+// Only what is actually formatted through `Debug` gets a bound: type
+// parameters appearing only inside `PhantomData`, function pointers, or
+// skipped fields are left unbounded.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Everywhere<'a, A, B, C: Trait, D, E, F> {
+    tuple: (u8, A),
+    array: [B; 2],
+    map: BTreeMap<String, Vec<Box<C::Value>>>,
+    reference: &'a Option<D>,
+    callback: fn(E) -> E,
+    marker: PhantomData<E>,
+    #[debug(skip)]
+    hidden: F,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    impl Trait for NotDebug {
+        type Value = u8;
+    }
+
+    assert_debug::<Everywhere<u8, u16, NotDebug, u32, NotDebug, NotDebug>>();
+}
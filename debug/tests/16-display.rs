@@ -0,0 +1,67 @@
+// `CustomDisplay` derives `Display` from a format string given in a
+// `#[display("...")]` attribute, either on the struct or on each enum variant.
+// The format string refers to fields by name, or as `_0`, `_1`, ... for tuple
+// fields. An attribute on an enum itself applies to every variant without one.
+//
+// Bounds are inferred the same way as for `CustomDebug`, but only for the
+// fields that the format strings mention, and through the trait each
+// placeholder formats with: `Display` for `{a}`, `Debug` for `{a:?}`, and so
+// on.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{from} -> {to}")]
+pub struct Edge<T> {
+    from: T,
+    to: T,
+}
+
+#[derive(CustomDisplay)]
+#[display("unknown")]
+pub enum Shape<T> {
+    #[display("circle of radius {radius}")]
+    Circle { radius: f64 },
+    #[display("{_0}x{_1} rectangle")]
+    Rectangle(u32, u32),
+    Other(PhantomData<T>),
+}
+
+#[derive(CustomDisplay)]
+#[display("{label} = {value:?}")]
+pub struct Labelled<T, U> {
+    label: &'static str,
+    value: T,
+    unused: U,
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    struct NotDisplay;
+
+    assert_display::<Shape<NotDisplay>>();
+    assert_display::<Labelled<Vec<u8>, NotDisplay>>();
+
+    let edge = Edge { from: 1, to: 2 };
+    assert_eq!(edge.to_string(), "1 -> 2");
+    assert_eq!(format!("{:?}", edge), "Edge { from: 1, to: 2 }");
+
+    let circle = Shape::<NotDisplay>::Circle { radius: 1.5 };
+    assert_eq!(circle.to_string(), "circle of radius 1.5");
+
+    let rectangle = Shape::<NotDisplay>::Rectangle(3, 4);
+    assert_eq!(rectangle.to_string(), "3x4 rectangle");
+
+    let other = Shape::<NotDisplay>::Other(PhantomData);
+    assert_eq!(other.to_string(), "unknown");
+
+    let labelled = Labelled {
+        label: "bytes",
+        value: vec![1u8, 2],
+        unused: NotDisplay,
+    };
+    assert_eq!(labelled.to_string(), "bytes = [1, 2]");
+}
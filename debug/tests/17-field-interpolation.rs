@@ -0,0 +1,31 @@
+// Format strings in `#[debug = "..."]` can refer to sibling fields, and to
+// expressions built from them, with `{self.field}` placeholders. Positional
+// placeholders keep referring to the annotated field itself.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Buffer {
+    #[debug = "{self.len} bytes at {self.ptr:p}"]
+    ptr: *const u8,
+    len: usize,
+    #[debug = "{:?} ({self.items.len()} items)"]
+    items: Vec<u8>,
+}
+
+fn main() {
+    let items = vec![1, 2, 3];
+    let buffer = Buffer {
+        ptr: items.as_ptr(),
+        len: items.len(),
+        items: items.clone(),
+    };
+
+    let debug = format!("{:?}", buffer);
+    let expected = format!(
+        "Buffer {{ ptr: 3 bytes at {:p}, len: 3, items: [1, 2, 3] (3 items) }}",
+        items.as_ptr(),
+    );
+
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,13 @@
+// A `{self.field}` placeholder naming a field that does not exist is reported
+// on the format string.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Buffer {
+    #[debug = "{self.length} bytes"]
+    data: Vec<u8>,
+    len: usize,
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+// `#[debug(hex)]` and `#[debug(bin)]` print integer fields in hexadecimal or
+// binary, and `#[debug(truncate = N)]` prints at most N elements of a long
+// slice or N characters of a long string, followed by the total length.
+// A generic `hex` or `bin` field is bounded by `LowerHex` or `Binary` rather
+// than `Debug`.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(hex)]
+    id: u32,
+    #[debug(bin)]
+    flags: u8,
+    #[debug(truncate = 4)]
+    payload: Vec<u8>,
+    #[debug(truncate = 5)]
+    note: String,
+    #[debug(truncate = 8)]
+    short: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub struct Reg<T, U> {
+    #[debug(hex)]
+    v: T,
+    #[debug(bin)]
+    mask: U,
+}
+
+fn main() {
+    let packet = Packet {
+        id: 0xbeef,
+        flags: 0b101,
+        payload: (0..100).collect(),
+        note: "hello world".to_owned(),
+        short: "hi",
+    };
+
+    let debug = format!("{:?}", packet);
+    let expected = concat!(
+        r#"Packet { id: 0xbeef, flags: 0b101, "#,
+        r#"payload: [0, 1, 2, 3, ...] (100 items), "#,
+        r#"note: "hello"... (11 bytes), short: "hi" }"#,
+    );
+
+    assert_eq!(debug, expected);
+
+    let reg = Reg { v: 255u8, mask: 6u16 };
+    assert_eq!(format!("{:?}", reg), "Reg { v: 0xff, mask: 0b110 }");
+}
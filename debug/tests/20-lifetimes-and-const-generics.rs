@@ -0,0 +1,47 @@
+// Structs with lifetime parameters, const generic parameters and defaulted
+// type parameters. Only type parameters get a `Debug` bound, and defaults are
+// left out of the generated impl.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct RingBuffer<T = u8, const N: usize = 4> {
+    items: [T; N],
+    head: usize,
+}
+
+#[derive(CustomDebug)]
+pub struct Borrowed<'a, 'b: 'a, T: ?Sized, const LEN: usize> {
+    name: &'a str,
+    value: &'b T,
+    bytes: &'a [u8; LEN],
+}
+
+#[derive(CustomDebug)]
+pub struct Capacity<const N: usize>;
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_debug::<RingBuffer>();
+    assert_debug::<RingBuffer<u16, 8>>();
+    assert_debug::<Borrowed<'static, 'static, str, 2>>();
+    assert_debug::<Capacity<16>>();
+
+    let ring = RingBuffer::<u8, 3> {
+        items: [1, 2, 3],
+        head: 1,
+    };
+    assert_eq!(format!("{:?}", ring), "RingBuffer { items: [1, 2, 3], head: 1 }");
+
+    let borrowed = Borrowed {
+        name: "x",
+        value: "y",
+        bytes: &[1, 2],
+    };
+    let expected = r#"Borrowed { name: "x", value: "y", bytes: [1, 2] }"#;
+    assert_eq!(format!("{:?}", borrowed), expected);
+
+    assert_eq!(format!("{:?}", Capacity::<16>), "Capacity");
+}
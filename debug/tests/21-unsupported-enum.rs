@@ -0,0 +1,12 @@
+// Deriving `CustomDebug` on an enum is reported as an error on the `enum`
+// keyword rather than making the macro panic.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+fn main() {}
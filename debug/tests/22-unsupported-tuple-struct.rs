@@ -0,0 +1,8 @@
+// Tuple structs are only supported as `#[debug(transparent)]` newtypes.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Point(i32, i32);
+
+fn main() {}
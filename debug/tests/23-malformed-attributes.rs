@@ -0,0 +1,24 @@
+// Malformed or unknown `debug` attributes are reported on the offending
+// tokens instead of being silently ignored.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct NotAString {
+    #[debug = 5]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct BarePath {
+    #[debug]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(bounds = "T: Clone")]
+pub struct UnknownOption<T> {
+    field: T,
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+// `#[debug(expr = "...")]` prints the value of an arbitrary expression in place
+// of the field, and `#[debug(as = "Wrapper")]` formats `Wrapper(&self.field)`
+// instead of the field itself.
+
+use derive_debug::CustomDebug;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+
+pub struct SortedKeys<'a, V>(&'a HashMap<&'static str, V>);
+
+impl<V> Debug for SortedKeys<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut keys = self.0.keys().collect::<Vec<_>>();
+        keys.sort();
+        f.debug_set().entries(keys).finish()
+    }
+}
+
+pub struct Opaque;
+
+#[derive(CustomDebug)]
+pub struct Registry {
+    #[debug(expr = "self.items.len()")]
+    items: Vec<Opaque>,
+    #[debug(as = "SortedKeys")]
+    handlers: HashMap<&'static str, Opaque>,
+}
+
+fn main() {
+    let registry = Registry {
+        items: vec![Opaque, Opaque],
+        handlers: HashMap::from([("b", Opaque), ("a", Opaque)]),
+    };
+
+    let debug = format!("{:?}", registry);
+    let expected = r#"Registry { items: 2, handlers: {"a", "b"} }"#;
+
+    assert_eq!(debug, expected);
+}
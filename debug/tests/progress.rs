@@ -9,4 +9,20 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-skip-and-redact.rs");
+    t.pass("tests/10-rename-and-transparent.rs");
+    t.pass("tests/11-compact-and-sorted.rs");
+    t.pass("tests/12-multiple-attributes.rs");
+    t.compile_fail("tests/13-conflicting-attributes.rs");
+    t.pass("tests/14-bound-lists.rs");
+    t.pass("tests/15-recursive-bounds.rs");
+    t.pass("tests/16-display.rs");
+    t.pass("tests/17-field-interpolation.rs");
+    t.compile_fail("tests/18-unknown-interpolated-field.rs");
+    t.pass("tests/19-format-presets.rs");
+    t.pass("tests/20-lifetimes-and-const-generics.rs");
+    t.compile_fail("tests/21-unsupported-enum.rs");
+    t.compile_fail("tests/22-unsupported-tuple-struct.rs");
+    t.compile_fail("tests/23-malformed-attributes.rs");
+    t.pass("tests/24-expr-and-wrapper.rs");
 }
@@ -0,0 +1,94 @@
+// Compares two ways of expanding a 1024-iteration body: substituting the whole
+// body in each iteration, and parsing it once into a template whose slots are
+// substituted in each iteration. Both must produce the same tokens.
+//
+// The macro's internals are compiled into the benchmark directly, because a
+// proc-macro crate cannot export anything but its macros.
+//
+//     cargo bench -p seq
+
+#[path = "../src/eval.rs"]
+mod eval;
+#[allow(dead_code)]
+#[path = "../src/expand.rs"]
+mod expand;
+#[path = "../src/template.rs"]
+mod template;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use proc_macro2::TokenStream;
+
+use crate::expand::{substitute_target, Bindings, Position, Sequence};
+use crate::template::Template;
+
+const RUNS: u32 = 20;
+
+fn main() {
+    let arms = (0..64)
+        .map(|i| format!("{i} => \"arm {i}\","))
+        .collect::<String>();
+    let input = format!(
+        "N in 0..1024 {{
+            struct Register~N;
+
+            type Reg~N = Register~N;
+
+            impl Register~N {{
+                const INDEX: usize = N;
+                const OFFSET: usize = #{{N * 4}};
+
+                fn read_~N(&self) -> u32 {{
+                    Self::INDEX as u32
+                }}
+
+                fn write_~N~_masked(&mut self, _value: u32) {{}}
+
+                fn describe(&self, value: u32) -> &'static str {{
+                    match value & 0xff {{
+                        {arms}
+                        _ => \"other\",
+                    }}
+                }}
+            }}
+        }}"
+    );
+    let sequence = syn::parse_str::<Sequence>(&input).unwrap();
+    let iterations = sequence.iterations();
+    let len = iterations.len();
+    let bindings = iterations
+        .iter()
+        .enumerate()
+        .map(|(index, substitutions)| {
+            Bindings::new(&sequence.idents, substitutions, Position { index, len })
+        })
+        .collect::<Vec<_>>();
+
+    let substituted = bench("substitute", || {
+        bindings
+            .iter()
+            .map(|bindings| substitute_target(sequence.content.clone(), bindings))
+            .collect::<syn::Result<TokenStream>>()
+            .unwrap()
+    });
+    let instantiated = bench("template", || {
+        let template = Template::new(sequence.content.clone(), &sequence.idents);
+        bindings
+            .iter()
+            .map(|bindings| template.instantiate(bindings))
+            .collect::<syn::Result<TokenStream>>()
+            .unwrap()
+    });
+    assert_eq!(substituted.to_string(), instantiated.to_string());
+}
+
+fn bench(name: &str, mut expand: impl FnMut() -> TokenStream) -> TokenStream {
+    let output = expand();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(expand());
+    }
+    println!("{name:<12} {:?} per expansion", start.elapsed() / RUNS);
+    output
+}
@@ -0,0 +1,59 @@
+use syn::{BinOp, Expr, Lit, UnOp};
+
+/// Evaluates an integer expression made of literals, parentheses and the
+/// arithmetic, bitwise and shift operators.
+pub(crate) fn evaluate(expr: &Expr) -> syn::Result<i64> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(lit) => lit.base10_parse(),
+            Lit::Char(lit) => Ok(lit.value() as i64),
+            Lit::Byte(lit) => Ok(lit.value() as i64),
+            lit => Err(syn::Error::new(lit.span(), "expected integer literal")),
+        },
+        Expr::Paren(expr) => evaluate(&expr.expr),
+        Expr::Group(expr) => evaluate(&expr.expr),
+        Expr::Unary(expr) => {
+            let value = evaluate(&expr.expr)?;
+            match expr.op {
+                UnOp::Neg(_) => value.checked_neg().ok_or_else(|| overflow(expr)),
+                UnOp::Not(_) => Ok(!value),
+                _ => Err(unsupported(expr)),
+            }
+        }
+        Expr::Binary(expr) => {
+            let left = evaluate(&expr.left)?;
+            let right = evaluate(&expr.right)?;
+            let value = match expr.op {
+                BinOp::Add(_) => left.checked_add(right),
+                BinOp::Sub(_) => left.checked_sub(right),
+                BinOp::Mul(_) => left.checked_mul(right),
+                BinOp::Div(_) => left.checked_div(right),
+                BinOp::Rem(_) => left.checked_rem(right),
+                BinOp::BitAnd(_) => Some(left & right),
+                BinOp::BitOr(_) => Some(left | right),
+                BinOp::BitXor(_) => Some(left ^ right),
+                BinOp::Shl(_) => u32::try_from(right)
+                    .ok()
+                    .and_then(|right| left.checked_shl(right)),
+                BinOp::Shr(_) => u32::try_from(right)
+                    .ok()
+                    .and_then(|right| left.checked_shr(right)),
+                _ => return Err(unsupported(expr)),
+            };
+            value.ok_or_else(|| overflow(expr))
+        }
+        Expr::Path(path) => Err(syn::Error::new_spanned(
+            path,
+            "cannot find this loop variable in the enclosing `seq!`",
+        )),
+        expr => Err(unsupported(expr)),
+    }
+}
+
+fn unsupported(expr: impl quote::ToTokens) -> syn::Error {
+    syn::Error::new_spanned(expr, "unsupported expression, expected integer arithmetic")
+}
+
+fn overflow(expr: impl quote::ToTokens) -> syn::Error {
+    syn::Error::new_spanned(expr, "integer overflow or division by zero")
+}
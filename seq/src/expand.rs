@@ -0,0 +1,1122 @@
+use itertools::{Itertools, MultiPeek};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+
+use crate::eval;
+use crate::template::Template;
+
+/// The most iterations a `seq!` may expand to, so that a typo such as
+/// `0..10_000_000` is reported instead of stalling the compiler.
+const MAX_ITERATIONS: u64 = 65_536;
+
+#[derive(Debug)]
+pub(crate) struct Sequence {
+    pub(crate) idents: Vec<syn::Ident>,
+    sources: Vec<Source>,
+    zip: bool,
+    /// Set by a leading `#[mixed_site]`, which makes pasted identifiers
+    /// resolve at the macro definition site, like `macro_rules!` locals.
+    pub(crate) mixed_site: bool,
+    pub(crate) content: TokenStream,
+}
+
+impl syn::parse::Parse for Sequence {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut mixed_site = false;
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            // #[mixed_site]
+            if !attr.path().is_ident("mixed_site") {
+                return Err(syn::Error::new_spanned(attr, "expected `#[mixed_site]`"));
+            }
+            attr.meta.require_path_only()?;
+            mixed_site = true;
+        }
+        let idents = if input.peek(syn::token::Paren) {
+            // (A, B) in ...
+            let idents;
+            let _ = syn::parenthesized!(idents in input); // Discard
+            idents
+                .parse_terminated(syn::Ident::parse, syn::Token![,])?
+                .into_iter()
+                .collect()
+        } else {
+            vec![input.parse()?]
+        };
+        let _ = input.parse::<syn::Token![in]>()?; // Discard
+        let ranges_span = input.span();
+        let zip = input.peek(syn::Ident) && input.peek2(syn::token::Paren);
+        let mut sources = if zip {
+            // zip(start..end, start..end)
+            let keyword = input.parse::<syn::Ident>()?;
+            if keyword != "zip" {
+                return Err(syn::Error::new(keyword.span(), "expected `zip`"));
+            }
+            let ranges;
+            let _ = syn::parenthesized!(ranges in input); // Discard
+            ranges
+                .parse_terminated(Source::parse, syn::Token![,])?
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else {
+            // start..end, start..end
+            let mut ranges = vec![input.parse()?];
+            while input.peek(syn::Token![,]) {
+                let _ = input.parse::<syn::Token![,]>()?; // Discard
+                ranges.push(input.parse()?);
+            }
+            ranges
+        };
+        if idents.len() > 1 && sources.len() == 1 {
+            // (Name, Ty) in [(A, u8), (B, u16)]
+            sources = vec![sources.remove(0).destructure(idents.len())?];
+        }
+        let width = sources.iter().map(Source::width).sum::<usize>();
+        if idents.len() != width {
+            return Err(syn::Error::new(
+                ranges_span,
+                format!(
+                    "expected {} ranges or lists, one for each loop variable, found {}",
+                    idents.len(),
+                    width,
+                ),
+            ));
+        }
+        let iterations = if zip {
+            sources.iter().map(Source::len).min().unwrap_or(0)
+        } else {
+            sources
+                .iter()
+                .try_fold(1u64, |count, source| count.checked_mul(source.len()))
+                .unwrap_or(u64::MAX)
+        };
+        if iterations > MAX_ITERATIONS {
+            return Err(syn::Error::new(
+                ranges_span,
+                format!("too many iterations, `seq!` expands to at most {MAX_ITERATIONS}"),
+            ));
+        }
+        let content;
+        let _ = syn::braced!(content in input); // Discard
+        let content = content.parse()?;
+        Ok(Sequence {
+            idents,
+            sources,
+            zip,
+            mixed_site,
+            content,
+        })
+    }
+}
+
+impl Sequence {
+    /// The values of the loop variables for each iteration: the cartesian
+    /// product of the ranges and lists, or their zip.
+    pub(crate) fn iterations(&self) -> Vec<Vec<Substitution>> {
+        let rows = self.sources.iter().map(Source::rows).collect::<Vec<_>>();
+        if self.zip {
+            let len = rows.iter().map(Vec::len).min().unwrap_or(0);
+            return (0..len)
+                .map(|i| rows.iter().flat_map(|rows| rows[i].clone()).collect())
+                .collect();
+        }
+        rows.into_iter()
+            .multi_cartesian_product()
+            .map(|row| row.concat())
+            .collect()
+    }
+}
+
+/// Where the values of the loop variables come from.
+#[derive(Clone, Debug)]
+enum Source {
+    Range(SequenceRange),
+    /// `[a, b, c]`, or `[(a, x), (b, y)]` destructured into two variables:
+    /// arbitrary tokens, substituted as they are written.
+    List(Vec<Vec<TokenStream>>),
+}
+
+impl Source {
+    /// How many loop variables the source binds.
+    fn width(&self) -> usize {
+        match self {
+            Source::Range(_) => 1,
+            Source::List(rows) => rows.first().map_or(1, Vec::len),
+        }
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            Source::Range(range) => range.len,
+            Source::List(rows) => rows.len() as u64,
+        }
+    }
+
+    fn rows(&self) -> Vec<Vec<Substitution>> {
+        match self {
+            Source::Range(range) => range.clone().map(|value| vec![value]).collect(),
+            Source::List(rows) => rows
+                .iter()
+                .map(|row| row.iter().cloned().map(Substitution::element).collect())
+                .collect(),
+        }
+    }
+
+    /// Splits each element of a list, which must be a tuple, into one element
+    /// for each of `width` loop variables.
+    fn destructure(self, width: usize) -> syn::Result<Self> {
+        let Source::List(rows) = self else {
+            return Ok(self);
+        };
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let element = TokenStream::from_iter(row);
+                let mut tokens = element.clone().into_iter();
+                if let (Some(TokenTree::Group(group)), None) = (tokens.next(), tokens.next()) {
+                    let parts = split_elements(group.stream());
+                    if group.delimiter() == Delimiter::Parenthesis && parts.len() == width {
+                        return Ok(parts);
+                    }
+                }
+                Err(syn::Error::new_spanned(
+                    element,
+                    format!("expected a tuple of {width} elements, one for each loop variable"),
+                ))
+            })
+            .collect::<syn::Result<_>>()?;
+        Ok(Source::List(rows))
+    }
+}
+
+impl syn::parse::Parse for Source {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if !input.peek(syn::token::Bracket) {
+            return input.parse().map(Source::Range);
+        }
+        // [a, b, c]
+        let elements;
+        let bracket = syn::bracketed!(elements in input);
+        let elements = split_elements(elements.parse()?);
+        if elements.is_empty() {
+            let message = "list is empty, so the body would never be expanded";
+            return Err(syn::Error::new(bracket.span.join(), message));
+        }
+        Ok(Source::List(
+            elements.into_iter().map(|element| vec![element]).collect(),
+        ))
+    }
+}
+
+/// Splits a comma separated list, leaving alone the commas between angle
+/// brackets, as in `HashMap<K, V>`.
+fn split_elements(tokens: TokenStream) -> Vec<TokenStream> {
+    let mut elements = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut arrow = false;
+    for tt in tokens {
+        let joint = match &tt {
+            TokenTree::Punct(punct) => {
+                match punct.as_char() {
+                    ',' if depth == 0 => {
+                        elements.push(Vec::new());
+                        arrow = false;
+                        continue;
+                    }
+                    '<' => depth += 1,
+                    // -> and =>
+                    '>' if !arrow => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                matches!(punct.as_char(), '-' | '=') && punct.spacing() == Spacing::Joint
+            }
+            _ => false,
+        };
+        arrow = joint;
+        elements.last_mut().unwrap().push(tt);
+    }
+    if elements.last().is_some_and(Vec::is_empty) {
+        elements.pop();
+    }
+    elements.into_iter().map(TokenStream::from_iter).collect()
+}
+
+/// The built-in variables, as in `#index`.
+pub(crate) const METADATA: [&str; 4] = ["index", "len", "first", "last"];
+
+/// The values bound to the loop variables in one iteration, along with the
+/// names bound by nested `seq!` invocations, which are left for those to
+/// substitute.
+#[derive(Clone)]
+pub(crate) struct Bindings<'a> {
+    values: Vec<(&'a Ident, &'a Substitution)>,
+    reserved: Vec<Ident>,
+    /// Where the iteration falls in the sequence, for `#index`, `#len`,
+    /// `#first` and `#last`. Left unset inside nested `seq!` invocations.
+    position: Option<Position>,
+    mixed_site: bool,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Position {
+    pub(crate) index: usize,
+    pub(crate) len: usize,
+}
+
+impl<'a> Bindings<'a> {
+    pub(crate) fn new(
+        idents: &'a [Ident],
+        substitutions: &'a [Substitution],
+        position: Position,
+    ) -> Self {
+        Bindings {
+            values: idents.iter().zip(substitutions).collect(),
+            reserved: Vec::new(),
+            position: Some(position),
+            mixed_site: false,
+        }
+    }
+
+    pub(crate) fn mixed_site(self, mixed_site: bool) -> Self {
+        Bindings { mixed_site, ..self }
+    }
+
+    fn get(&self, ident: &Ident) -> Option<&'a Substitution> {
+        self.values
+            .iter()
+            .find(|(target, _)| *target == ident)
+            .map(|(_, substitution)| *substitution)
+    }
+
+    /// The bindings visible in the body of a nested `seq!` binding `names`,
+    /// which shadow the outer variables of the same name.
+    fn nested(&self, names: Vec<Ident>) -> Self {
+        Bindings {
+            values: self
+                .values
+                .iter()
+                .filter(|(target, _)| !names.contains(target))
+                .copied()
+                .collect(),
+            reserved: self.reserved.iter().cloned().chain(names).collect(),
+            position: None,
+            mixed_site: self.mixed_site,
+        }
+    }
+
+    /// The value of the built-in variable `#name`, if there is one.
+    fn metadata(&self, name: &Ident) -> Option<TokenTree> {
+        let Position { index, len } = self.position?;
+        let tt = match name.to_string().as_str() {
+            "index" => Literal::usize_unsuffixed(index).into(),
+            "len" => Literal::usize_unsuffixed(len).into(),
+            "first" => Ident::new(if index == 0 { "true" } else { "false" }, name.span()).into(),
+            "last" => {
+                Ident::new(if index + 1 == len { "true" } else { "false" }, name.span()).into()
+            }
+            _ => return None,
+        };
+        Some(tt)
+    }
+}
+
+/// The values of a sequence: `start` followed by `len - 1` more values, each
+/// `step` apart.
+#[derive(Clone, Debug)]
+struct SequenceRange {
+    start: i64,
+    step: i64,
+    len: u64,
+    kind: ValueKind,
+}
+
+/// The kind of literal the values of a sequence are substituted as.
+#[derive(Clone, Debug, PartialEq)]
+enum ValueKind {
+    /// An integer literal, with its type suffix, if any.
+    Int(String),
+    Char,
+    Byte,
+}
+
+/// A value of the sequence, as substituted for the loop variable.
+#[derive(Clone, Debug)]
+pub(crate) struct Substitution {
+    tokens: TokenTree,
+    /// The integer value, for arithmetic and formatted pasting.
+    value: Option<i64>,
+    /// How the value is spelled when pasted into an identifier with `~`, if
+    /// it can be pasted at all.
+    paste: Option<String>,
+}
+
+impl Substitution {
+    /// An element of a list. Several tokens are kept together in an invisible
+    /// group, as `macro_rules!` does for a `$ty` fragment.
+    fn element(tokens: TokenStream) -> Self {
+        let mut iter = tokens.clone().into_iter();
+        let tt = match (iter.next(), iter.next()) {
+            (Some(tt), None) => tt,
+            _ => {
+                return Substitution {
+                    tokens: Group::new(Delimiter::None, tokens).into(),
+                    value: None,
+                    paste: None,
+                }
+            }
+        };
+        let (value, paste) = match &tt {
+            TokenTree::Ident(ident) => {
+                let paste = ident.to_string().trim_start_matches("r#").to_owned();
+                (None, Some(paste))
+            }
+            // Chars and bytes behave as they do in a range; other literals,
+            // such as strings, have no value.
+            TokenTree::Literal(literal) => match syn::Lit::new(literal.clone()) {
+                syn::Lit::Int(lit) => (lit.base10_parse().ok(), pastable_literal(&tt)),
+                syn::Lit::Char(lit) => (
+                    Some(i64::from(u32::from(lit.value()))),
+                    pastable_char(lit.value()),
+                ),
+                syn::Lit::Byte(lit) => (
+                    Some(i64::from(lit.value())),
+                    pastable_char(char::from(lit.value())),
+                ),
+                _ => (None, pastable_literal(&tt)),
+            },
+            _ => (None, None),
+        };
+        Substitution {
+            tokens: tt,
+            value,
+            paste,
+        }
+    }
+
+    /// The spelling pasted in place of `ident`, the variable bound to it.
+    fn pasted(&self, ident: &Ident) -> syn::Result<String> {
+        self.paste.clone().ok_or_else(|| {
+            let message = match self.value {
+                Some(value) if value < 0 => {
+                    format!("cannot paste negative value `{value}` into an identifier")
+                }
+                _ => format!(
+                    "`{ident}` is bound to `{}`, which cannot be pasted into an identifier",
+                    self.tokens,
+                ),
+            };
+            syn::Error::new(ident.span(), message)
+        })
+    }
+}
+
+impl SequenceRange {
+    pub fn new(start: i64, end: i64, inclusive: bool, kind: ValueKind) -> Self {
+        let len = i128::from(end) - i128::from(start) + i128::from(inclusive);
+        Self {
+            start,
+            step: 1,
+            len: len.max(0) as u64,
+            kind,
+        }
+    }
+
+    /// Keeps every `step`th value, starting from the first one, or returns
+    /// `None` if the stride does not fit in an `i64`.
+    pub fn stepped(self, step: u64) -> Option<Self> {
+        Some(Self {
+            step: self.step.checked_mul(i64::try_from(step).ok()?)?,
+            len: self.len.div_ceil(step),
+            ..self
+        })
+    }
+
+    /// Yields the same values, last to first, or returns `None` if they cannot
+    /// be counted down in an `i64`.
+    pub fn reversed(self) -> Option<Self> {
+        // The offset of the last value may overflow an `i64` even though the
+        // value itself does not, as in `(i64::MIN..i64::MAX step 2).rev()`.
+        let offset = i128::from(self.step) * i128::from(self.len.saturating_sub(1));
+        Some(Self {
+            start: i64::try_from(i128::from(self.start) + offset).ok()?,
+            step: self.step.checked_neg()?,
+            ..self
+        })
+    }
+}
+
+impl syn::parse::Parse for SequenceRange {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Paren) {
+            // (start..end).rev()
+            let range;
+            let _ = syn::parenthesized!(range in input); // Discard
+            let range = range.parse::<SequenceRange>()?;
+            let _ = input.parse::<syn::Token![.]>()?; // Discard
+            let method = input.parse::<syn::Ident>()?;
+            if method != "rev" {
+                return Err(syn::Error::new(method.span(), "expected `rev`"));
+            }
+            let args;
+            let _ = syn::parenthesized!(args in input); // Discard
+            args.parse::<syn::parse::Nothing>()?;
+            return range
+                .reversed()
+                .ok_or_else(|| syn::Error::new(method.span(), "range is too large to reverse"));
+        }
+
+        let start_span = input.span();
+        let (start, start_kind) = parse_bound(input)?;
+        let _ = input.parse::<syn::Token![..]>()?; // Discard
+        let inclusive = input.peek(syn::Token![=]);
+        if inclusive {
+            let _ = input.parse::<syn::Token![=]>()?; // Discard
+        }
+        let end_span = input.span();
+        let (end, end_kind) = parse_bound(input)?;
+        let kind = match (start_kind, end_kind) {
+            (start, end) if start == end => start,
+            (ValueKind::Int(start), ValueKind::Int(end)) if start.is_empty() || end.is_empty() => {
+                ValueKind::Int(start + &end)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    end_span,
+                    "range bounds must be literals of the same type",
+                ))
+            }
+        };
+        if start > end {
+            let message = "range start greater than end, use `(start..end).rev()` to count down";
+            return Err(syn::Error::new(start_span, message));
+        }
+        if start == end && !inclusive {
+            let message = "range is empty, so the body would never be expanded";
+            return Err(syn::Error::new(start_span, message));
+        }
+        let range = SequenceRange::new(start, end, inclusive, kind);
+
+        // start..end step n
+        if input.peek(syn::Ident) {
+            let keyword = input.parse::<syn::Ident>()?;
+            if keyword != "step" {
+                return Err(syn::Error::new(keyword.span(), "expected `step`"));
+            }
+            let step = input.parse::<syn::LitInt>()?;
+            let value = step.base10_parse::<u64>()?;
+            if value == 0 {
+                return Err(syn::Error::new(step.span(), "step must be positive"));
+            }
+            return range.stepped(value).ok_or_else(|| {
+                syn::Error::new(step.span(), format!("step must be at most {}", i64::MAX))
+            });
+        }
+        Ok(range)
+    }
+}
+
+/// Parses a range bound: a literal, or integer arithmetic on literals such as
+/// `1 << 3`, possibly wrapped in the invisible groups that `macro_rules!`
+/// leaves around `$bound:expr` fragments.
+fn parse_bound(input: syn::parse::ParseStream) -> syn::Result<(i64, ValueKind)> {
+    let span = input.span();
+    let tokens = input.step(|cursor| {
+        let mut rest = *cursor;
+        let mut tokens = TokenStream::new();
+        while let Some((tt, next)) = rest.token_tree() {
+            let end = match &tt {
+                // .. and ..=
+                TokenTree::Punct(punct) => {
+                    punct.as_char() == ','
+                        || punct.as_char() == '.' && punct.spacing() == Spacing::Joint
+                }
+                TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+                TokenTree::Ident(ident) => ident == "step",
+                TokenTree::Literal(_) => false,
+            };
+            if end {
+                break;
+            }
+            tokens.extend([tt]);
+            rest = next;
+        }
+        Ok((tokens, rest))
+    })?;
+    if tokens.is_empty() {
+        return Err(syn::Error::new(span, "expected integer literal"));
+    }
+    if let Some(ident) = first_ident(tokens.clone()) {
+        return Err(syn::Error::new(ident.span(), "expected integer literal"));
+    }
+    let expr = syn::parse2::<syn::Expr>(tokens)?;
+    Ok((eval::evaluate(&expr)?, literal_kind(&expr)))
+}
+
+fn first_ident(tokens: TokenStream) -> Option<Ident> {
+    tokens.into_iter().find_map(|tt| match tt {
+        TokenTree::Ident(ident) => Some(ident),
+        TokenTree::Group(group) => first_ident(group.stream()),
+        _ => None,
+    })
+}
+
+/// The kind of a bound written as a single, possibly negated, literal. The
+/// result of arithmetic is an unsuffixed integer.
+fn literal_kind(expr: &syn::Expr) -> ValueKind {
+    match expr {
+        syn::Expr::Group(expr) => literal_kind(&expr.expr),
+        syn::Expr::Paren(expr) => literal_kind(&expr.expr),
+        syn::Expr::Unary(expr) if matches!(expr.op, syn::UnOp::Neg(_)) => literal_kind(&expr.expr),
+        syn::Expr::Lit(expr) => match &expr.lit {
+            syn::Lit::Char(_) => ValueKind::Char,
+            syn::Lit::Byte(_) => ValueKind::Byte,
+            syn::Lit::Int(lit) => ValueKind::Int(lit.suffix().to_owned()),
+            _ => ValueKind::Int(String::new()),
+        },
+        _ => ValueKind::Int(String::new()),
+    }
+}
+
+impl Iterator for SequenceRange {
+    type Item = Substitution;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.len == 0 {
+                return None;
+            }
+            let next = self.start;
+            self.start = self.start.wrapping_add(self.step);
+            self.len -= 1;
+
+            let span = proc_macro2::Span::call_site();
+            let substitution = match &self.kind {
+                ValueKind::Int(suffix) if suffix.is_empty() => Substitution {
+                    tokens: Literal::i64_unsuffixed(next).into(),
+                    value: Some(next),
+                    paste: (next >= 0).then(|| next.to_string()),
+                },
+                ValueKind::Int(suffix) => Substitution {
+                    tokens: syn::LitInt::new(&format!("{next}{suffix}"), span)
+                        .token()
+                        .into(),
+                    value: Some(next),
+                    paste: (next >= 0).then(|| next.to_string()),
+                },
+                // Skips the surrogate code points, which are not chars.
+                ValueKind::Char => match char::from_u32(next as u32) {
+                    Some(c) => Substitution {
+                        tokens: Literal::character(c).into(),
+                        value: Some(next),
+                        paste: pastable_char(c),
+                    },
+                    None => continue,
+                },
+                ValueKind::Byte => Substitution {
+                    tokens: syn::LitByte::new(next as u8, span).token().into(),
+                    value: Some(next),
+                    paste: pastable_char(char::from(next as u8)),
+                },
+            };
+            return Some(substitution);
+        }
+    }
+}
+
+/// Expands the body once for each iteration, or only its repeat sections if
+/// it has any.
+pub(crate) fn expand(sequence: Sequence) -> syn::Result<TokenStream> {
+    let iterations = sequence.iterations();
+    let Sequence {
+        idents,
+        mixed_site,
+        content,
+        ..
+    } = sequence;
+
+    if has_repeat_section(content.clone()) {
+        return repeat_section(content, &idents, &iterations, mixed_site);
+    }
+    let template = Template::new(content, &idents);
+    let len = iterations.len();
+    iterations
+        .iter()
+        .enumerate()
+        .map(|(index, substitutions)| {
+            let bindings = Bindings::new(&idents, substitutions, Position { index, len })
+                .mixed_site(mixed_site);
+            template.instantiate(&bindings)
+        })
+        .collect()
+}
+
+pub(crate) fn substitute_target(
+    content: TokenStream,
+    bindings: &Bindings,
+) -> syn::Result<TokenStream> {
+    let mut iter = content.into_iter().multipeek();
+    let mut output = Vec::new();
+
+    while let Some(tt) = iter.next() {
+        let tt = match tt {
+            TokenTree::Ident(ref ident) => {
+                let nested = nested_sequence(&mut iter, ident);
+                if let Some(names) = nested {
+                    output.push(tt);
+                    output.push(iter.next().unwrap()); // !
+                    let Some(TokenTree::Group(group)) = iter.next() else {
+                        unreachable!();
+                    };
+                    let stream = substitute_target(group.stream(), &bindings.nested(names))?;
+                    let mut replacement = Group::new(group.delimiter(), stream);
+                    replacement.set_span(group.span());
+                    output.push(replacement.into());
+                    continue;
+                }
+                parse_ident(&mut iter, ident, bindings)?.unwrap_or(tt)
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute_target(group.stream(), bindings)?;
+                Group::new(group.delimiter(), stream).into()
+            }
+            TokenTree::Punct(ref punct) if punct.as_char() == '#' => {
+                iter.reset_peek();
+                match iter.peek() {
+                    Some(TokenTree::Group(group))
+                        if group.delimiter() == Delimiter::Brace
+                            && !mentions(group.stream(), &bindings.reserved) =>
+                    {
+                        // #{N * 4}
+                        let span = group.span();
+                        let value = evaluate_tokens(group.stream(), bindings)?;
+                        iter.next();
+                        let mut literal = Literal::i64_unsuffixed(value);
+                        literal.set_span(span);
+                        literal.into()
+                    }
+                    Some(TokenTree::Ident(name)) => match bindings.metadata(name) {
+                        // #index, #len, #first, #last
+                        Some(mut value) => {
+                            value.set_span(name.span());
+                            iter.next();
+                            value
+                        }
+                        None => {
+                            iter.reset_peek();
+                            tt
+                        }
+                    },
+                    _ => {
+                        iter.reset_peek();
+                        tt
+                    }
+                }
+            }
+            tt => tt,
+        };
+        output.push(tt);
+    }
+
+    Ok(TokenStream::from_iter(output))
+}
+
+/// Whether any of `names` appears anywhere in `tokens`.
+fn mentions(tokens: TokenStream, names: &[Ident]) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => names.contains(&ident),
+        TokenTree::Group(group) => mentions(group.stream(), names),
+        _ => false,
+    })
+}
+
+/// Evaluates an integer expression over the loop variables at compile time.
+fn evaluate_tokens(tokens: TokenStream, bindings: &Bindings) -> syn::Result<i64> {
+    fn substitute_values(tokens: TokenStream, bindings: &Bindings) -> syn::Result<TokenStream> {
+        tokens
+            .into_iter()
+            .map(|tt| match tt {
+                TokenTree::Ident(ref ident) => match bindings.get(ident) {
+                    Some(substitution) => {
+                        let value = substitution.value.ok_or_else(|| {
+                            let message = format!("`{ident}` is not bound to an integer");
+                            syn::Error::new(ident.span(), message)
+                        })?;
+                        let mut literal = Literal::i64_unsuffixed(value);
+                        literal.set_span(ident.span());
+                        let stream = TokenStream::from(TokenTree::from(literal));
+                        Ok(Group::new(Delimiter::Parenthesis, stream).into())
+                    }
+                    None => Ok(tt),
+                },
+                TokenTree::Group(group) => {
+                    let stream = substitute_values(group.stream(), bindings)?;
+                    let mut replacement = Group::new(group.delimiter(), stream);
+                    replacement.set_span(group.span());
+                    Ok(replacement.into())
+                }
+                tt => Ok(tt),
+            })
+            .collect()
+    }
+
+    let expr = syn::parse2::<syn::Expr>(substitute_values(tokens, bindings)?)?;
+    eval::evaluate(&expr)
+}
+
+/// Recognizes a nested `seq!(...)` invocation starting at `ident`, and returns
+/// the loop variables it binds.
+pub(crate) fn nested_sequence(
+    iter: &mut MultiPeek<proc_macro2::token_stream::IntoIter>,
+    ident: &Ident,
+) -> Option<Vec<Ident>> {
+    iter.reset_peek();
+    let is_bang = iter
+        .peek()
+        .is_some_and(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '!'));
+    let names = match iter.peek() {
+        Some(TokenTree::Group(group)) if ident == "seq" && is_bang => {
+            // Skips attributes such as #[mixed_site].
+            let mut header = group.stream().into_iter();
+            let mut first = header.next();
+            while matches!(&first, Some(TokenTree::Punct(p)) if p.as_char() == '#') {
+                header.next(); // [...]
+                first = header.next();
+            }
+            match first {
+                Some(TokenTree::Ident(name)) => Some(vec![name]),
+                Some(TokenTree::Group(names)) => Some(
+                    names
+                        .stream()
+                        .into_iter()
+                        .filter_map(|tt| match tt {
+                            TokenTree::Ident(name) => Some(name),
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    iter.reset_peek();
+    names
+}
+
+/// Expands every `#( ... )*` section in `content`, at any depth, once per
+/// iteration, leaving the tokens around them untouched.
+fn repeat_section(
+    content: TokenStream,
+    idents: &[Ident],
+    iterations: &[Vec<Substitution>],
+    mixed_site: bool,
+) -> syn::Result<TokenStream> {
+    let mut iter = content.into_iter().multipeek();
+    let mut output = Vec::new();
+
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(ref punct) if punct.as_char() == '#' => {
+                let Some((body, separator)) = repeat_section_at(&mut iter) else {
+                    output.push(tt);
+                    continue;
+                };
+                let template = Template::new(body.stream(), idents);
+                let len = iterations.len();
+                for (index, substitutions) in iterations.iter().enumerate() {
+                    if index > 0 {
+                        output.extend(separator.clone());
+                    }
+                    let bindings = Bindings::new(idents, substitutions, Position { index, len })
+                        .mixed_site(mixed_site);
+                    output.extend(template.instantiate(&bindings)?);
+                }
+            }
+            TokenTree::Ident(ref ident) => {
+                // The repeat sections of a nested `seq!` belong to it.
+                let nested = nested_sequence(&mut iter, ident).is_some();
+                output.push(tt);
+                if nested {
+                    output.extend(iter.next());
+                    output.extend(iter.next());
+                }
+            }
+            TokenTree::Group(group) => {
+                let stream = repeat_section(group.stream(), idents, iterations, mixed_site)?;
+                let mut replacement = Group::new(group.delimiter(), stream);
+                replacement.set_span(group.span());
+                output.push(replacement.into());
+            }
+            tt => output.push(tt),
+        }
+    }
+
+    Ok(TokenStream::from_iter(output))
+}
+
+/// Recognizes the rest of a repeat section after its `#`, as in `#( ... )*`
+/// or `#( ... ),*`, and consumes it, returning its body and separator.
+fn repeat_section_at(
+    iter: &mut MultiPeek<proc_macro2::token_stream::IntoIter>,
+) -> Option<(Group, Option<TokenTree>)> {
+    iter.reset_peek();
+    let body = match iter.peek() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.clone()
+        }
+        _ => {
+            iter.reset_peek();
+            return None;
+        }
+    };
+    let separator = match (iter.peek().cloned(), iter.peek()) {
+        (Some(TokenTree::Punct(star)), _) if star.as_char() == '*' => None,
+        (Some(TokenTree::Punct(separator)), Some(TokenTree::Punct(star)))
+            if star.as_char() == '*' =>
+        {
+            Some(TokenTree::Punct(separator))
+        }
+        _ => {
+            iter.reset_peek();
+            return None;
+        }
+    };
+    iter.next();
+    if separator.is_some() {
+        iter.next();
+    }
+    iter.next();
+    Some((body, separator))
+}
+
+/// Pastes `ident` together with the tokens following it, as in
+/// `prefix~N~suffix`, or substitutes it when it is itself a loop variable.
+///
+/// The pasted identifier spans all of the joined tokens where the compiler
+/// supports joining spans, and the prefix alone otherwise. It is raw when the
+/// prefix is, as in `r#type~N`.
+fn parse_ident(
+    iter: &mut MultiPeek<proc_macro2::token_stream::IntoIter>,
+    ident: &Ident,
+    bindings: &Bindings,
+) -> syn::Result<Option<TokenTree>> {
+    iter.reset_peek();
+    let is_paste = iter
+        .peek()
+        .is_some_and(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '~'));
+    iter.reset_peek();
+    if !is_paste {
+        return Ok(bindings
+            .get(ident)
+            .map(|substitution| substitution.tokens.clone()));
+    }
+
+    let spelling = ident.to_string();
+    let raw = spelling.starts_with("r#");
+    let mut name = spelling.trim_start_matches("r#").to_owned();
+    let mut span = ident.span();
+    let mut pasted = false;
+    loop {
+        iter.reset_peek();
+        let is_tilde = iter
+            .peek()
+            .is_some_and(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '~'));
+        if !is_tilde {
+            break;
+        }
+        let Some(next) = iter.peek() else {
+            break;
+        };
+        if let Some(value) = paste_value(next, bindings) {
+            name.push_str(&value?);
+        } else if let TokenTree::Ident(suffix) = next {
+            if !pasted || bindings.reserved.contains(suffix) {
+                break;
+            }
+            name.push_str(suffix.to_string().trim_start_matches("r#"));
+        } else if let Some(suffix) = pastable_literal(next) {
+            // Foo~2
+            name.push_str(&suffix);
+        } else {
+            break;
+        }
+        span = span.join(next.span()).unwrap_or(span);
+        iter.next(); // ~
+        iter.next(); // N, or suffix
+        pasted = true;
+    }
+    iter.reset_peek();
+
+    if pasted {
+        if bindings.mixed_site {
+            span = span.resolved_at(Span::mixed_site());
+        }
+        if !is_ident(&name) {
+            let message = format!("pasting produces `{name}`, which is not a valid identifier");
+            return Err(syn::Error::new(span, message));
+        }
+        let ident = if raw {
+            Ident::new_raw(&name, span)
+        } else {
+            Ident::new(&name, span)
+        };
+        Ok(Some(ident.into()))
+    } else if let Some(substitution) = bindings.get(ident) {
+        Ok(Some(substitution.tokens.clone()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Whether `name` is spelled as an identifier, and so can be given to
+/// `Ident::new` without panicking.
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || unicode_ident::is_xid_start(c))
+        && chars.all(unicode_ident::is_xid_continue)
+        && name != "_"
+}
+
+/// The spelling of `c` if it can continue an identifier, unlike `' '` or
+/// `'\u{80}'`.
+fn pastable_char(c: char) -> Option<String> {
+    unicode_ident::is_xid_continue(c).then(|| c.to_string())
+}
+
+/// The spelling of `tt` if it is a literal that can end an identifier, such
+/// as `2` or `1u8` but not `"str"` or `1.5`.
+fn pastable_literal(tt: &TokenTree) -> Option<String> {
+    let TokenTree::Literal(literal) = tt else {
+        return None;
+    };
+    let spelling = literal.to_string();
+    spelling
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_')
+        .then_some(spelling)
+}
+
+/// Spells out the value pasted by `N`, or by a `{N:spec}` formatting
+/// directive, when `tt` is one of those.
+fn paste_value(tt: &TokenTree, bindings: &Bindings) -> Option<syn::Result<String>> {
+    match tt {
+        TokenTree::Ident(ident) => bindings
+            .get(ident)
+            .map(|substitution| substitution.pasted(ident)),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            let bound = bindings
+                .values
+                .iter()
+                .map(|(ident, _)| (*ident).clone())
+                .collect::<Vec<_>>();
+            if !mentions(group.stream(), &bound) || mentions(group.stream(), &bindings.reserved) {
+                return None;
+            }
+            let mut tokens = group.stream().into_iter();
+            let expr = tokens
+                .by_ref()
+                .take_while(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ':'))
+                .collect::<TokenStream>();
+            let spec = match (tokens.next(), tokens.next()) {
+                (None, _) => None,
+                (Some(spec), None) => Some(spec),
+                (Some(_), Some(_)) => {
+                    let message = "expected `{N}`, `{N:spec}` or `{expr:spec}`";
+                    return Some(Err(syn::Error::new(group.span(), message)));
+                }
+            };
+            // A lone variable is pasted as it is spelled, e.g. as a character.
+            let mut lone = expr.clone().into_iter();
+            if let (Some(TokenTree::Ident(ident)), None, None) = (lone.next(), lone.next(), &spec) {
+                return bindings
+                    .get(&ident)
+                    .map(|substitution| substitution.pasted(&ident));
+            }
+            let pasted = evaluate_tokens(expr, bindings).and_then(|value| {
+                if value < 0 {
+                    let message =
+                        format!("cannot paste negative value `{value}` into an identifier");
+                    return Err(syn::Error::new(group.span(), message));
+                }
+                match &spec {
+                    Some(spec) => PasteFormat::parse(spec).map(|format| format.apply(value)),
+                    None => Ok(value.to_string()),
+                }
+            });
+            Some(pasted)
+        }
+        _ => None,
+    }
+}
+
+/// The `spec` of a `{N:spec}` formatting directive: an optional zero padded
+/// width, followed by an optional `x`, `X`, `o` or `b` radix.
+///
+/// Only the braced form is supported, because a bare `N:spec` could not be told
+/// apart from a pasted field name in a struct literal, as in `reg~N: 0`.
+struct PasteFormat {
+    width: usize,
+    radix: Option<char>,
+}
+
+impl PasteFormat {
+    fn parse(spec: &TokenTree) -> syn::Result<Self> {
+        let spec_str = spec.to_string();
+        let radix_at = spec_str.find(|c: char| !c.is_ascii_digit());
+        let (width, radix) = spec_str.split_at(radix_at.unwrap_or(spec_str.len()));
+        let width = if width.is_empty() {
+            0
+        } else {
+            width
+                .parse()
+                .map_err(|err| syn::Error::new(spec.span(), err))?
+        };
+        let radix = match radix {
+            "" => None,
+            "x" | "X" | "o" | "b" => radix.chars().next(),
+            _ => {
+                return Err(syn::Error::new(
+                    spec.span(),
+                    "expected a width and/or one of `x`, `X`, `o` or `b`",
+                ))
+            }
+        };
+        Ok(PasteFormat { width, radix })
+    }
+
+    fn apply(&self, value: i64) -> String {
+        let width = self.width;
+        match self.radix {
+            None => format!("{value:0width$}"),
+            Some('x') => format!("{value:0width$x}"),
+            Some('X') => format!("{value:0width$X}"),
+            Some('o') => format!("{value:0width$o}"),
+            Some('b') => format!("{value:0width$b}"),
+            Some(_) => unreachable!(),
+        }
+    }
+}
+
+fn has_repeat_section(content: TokenStream) -> bool {
+    let mut iter = content.into_iter().multipeek();
+    while let Some(tt) = iter.next() {
+        let found = match tt {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                repeat_section_at(&mut iter).is_some()
+            }
+            TokenTree::Ident(ident) => {
+                if nested_sequence(&mut iter, &ident).is_some() {
+                    iter.next();
+                    iter.next();
+                }
+                false
+            }
+            TokenTree::Group(group) => has_repeat_section(group.stream()),
+            _ => false,
+        };
+        if found {
+            return true;
+        }
+    }
+    false
+}
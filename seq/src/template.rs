@@ -0,0 +1,116 @@
+use itertools::{Itertools, MultiPeek};
+use proc_macro2::{Delimiter, Group, Ident, TokenStream, TokenTree};
+
+use crate::expand::{nested_sequence, substitute_target, Bindings, METADATA};
+
+/// A body parsed once, ahead of the iterations, into runs of tokens that are
+/// copied as they are and slots that are substituted in each iteration.
+///
+/// A slot is a loop variable, an identifier followed by `~` pastes, a `#`
+/// directive, or a nested `seq!`, and is expanded by `substitute_target`, so
+/// that the result is the same as substituting the whole body.
+pub(crate) struct Template {
+    nodes: Vec<Node>,
+}
+
+enum Node {
+    Tokens(TokenStream),
+    Group(Delimiter, Template),
+    Slot(TokenStream),
+}
+
+impl Template {
+    pub(crate) fn new(content: TokenStream, idents: &[Ident]) -> Self {
+        let mut nodes = Vec::new();
+        let mut tokens = Vec::new();
+        let mut iter = content.into_iter().multipeek();
+
+        while let Some(tt) = iter.next() {
+            let node = match &tt {
+                TokenTree::Ident(ident) => slot_at_ident(&mut iter, ident, idents),
+                TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                    // #{N * 4}, #index
+                    iter.reset_peek();
+                    let is_directive = match iter.peek() {
+                        Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Brace,
+                        // Any other `#ident` is left alone, and the ident may
+                        // still start a paste, as in `#Foo~N`.
+                        Some(TokenTree::Ident(ident)) => METADATA.iter().any(|name| ident == name),
+                        _ => false,
+                    };
+                    iter.reset_peek();
+                    is_directive.then(|| {
+                        Node::Slot(TokenStream::from_iter([tt.clone(), iter.next().unwrap()]))
+                    })
+                }
+                TokenTree::Group(group) => {
+                    let template = Template::new(group.stream(), idents);
+                    template
+                        .has_slots()
+                        .then(|| Node::Group(group.delimiter(), template))
+                }
+                _ => None,
+            };
+            match node {
+                Some(node) => {
+                    if !tokens.is_empty() {
+                        nodes.push(Node::Tokens(tokens.drain(..).collect()));
+                    }
+                    nodes.push(node);
+                }
+                None => tokens.push(tt),
+            }
+        }
+        if !tokens.is_empty() {
+            nodes.push(Node::Tokens(tokens.into_iter().collect()));
+        }
+        Template { nodes }
+    }
+
+    fn has_slots(&self) -> bool {
+        self.nodes
+            .iter()
+            .any(|node| !matches!(node, Node::Tokens(_)))
+    }
+
+    pub(crate) fn instantiate(&self, bindings: &Bindings) -> syn::Result<TokenStream> {
+        let mut output = TokenStream::new();
+        for node in &self.nodes {
+            match node {
+                Node::Tokens(tokens) => output.extend(tokens.clone()),
+                Node::Group(delimiter, template) => {
+                    let stream = template.instantiate(bindings)?;
+                    output.extend([TokenTree::from(Group::new(*delimiter, stream))]);
+                }
+                Node::Slot(tokens) => output.extend(substitute_target(tokens.clone(), bindings)?),
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// The slot starting at `ident`, if there is one: a nested `seq!(...)`, or
+/// `ident` along with each `~` and token pasted onto it.
+fn slot_at_ident(
+    iter: &mut MultiPeek<proc_macro2::token_stream::IntoIter>,
+    ident: &Ident,
+    idents: &[Ident],
+) -> Option<Node> {
+    let mut slot = vec![TokenTree::Ident(ident.clone())];
+    if nested_sequence(iter, ident).is_some() {
+        slot.extend(iter.next()); // !
+        slot.extend(iter.next()); // (...)
+        return Some(Node::Slot(TokenStream::from_iter(slot)));
+    }
+    loop {
+        iter.reset_peek();
+        match iter.peek() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '~' => {}
+            _ => break,
+        }
+        slot.extend(iter.next()); // ~
+        slot.extend(iter.next()); // N, or suffix
+    }
+    iter.reset_peek();
+    (slot.len() > 1 || idents.contains(ident)).then(|| Node::Slot(TokenStream::from_iter(slot)))
+}
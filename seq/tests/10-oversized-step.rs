@@ -0,0 +1,8 @@
+// A step too large to count by in an `i64` is reported at the step, rather
+// than wrapping around.
+
+use seq::seq;
+
+const ZERO: [u8; 1] = seq!(N in (0..4 step 9223372036854775808).rev() { [#(N,)*] });
+
+fn main() {}
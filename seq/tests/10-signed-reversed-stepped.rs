@@ -0,0 +1,41 @@
+// Ranges may start at negative integers, be iterated in reverse with
+// `(start..end).rev()`, and skip values with `start..end step n`.
+
+use seq::seq;
+
+const SIGNED: [i32; 8] = seq!(N in -4..4 { [#(N,)*] });
+
+const REVERSED: [u8; 4] = seq!(N in (0..4).rev() { [#(N,)*] });
+
+const STEPPED: [usize; 8] = seq!(N in 0..64 step 8 { [#(N,)*] });
+
+const REVERSED_STEPPED: [usize; 3] = seq!(N in (0..=8 step 4).rev() { [#(N,)*] });
+
+const WIDE: [i64; 4] = seq!(N in (-9223372036854775807..9223372036854775807 step 4611686018427387904).rev() {
+    [#(N,)*]
+});
+
+seq!(N in 0..32 step 8 {
+    #[allow(dead_code)]
+    struct Registers {
+        #(
+            reg~N: u64,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(SIGNED, [-4, -3, -2, -1, 0, 1, 2, 3]);
+    assert_eq!(REVERSED, [3, 2, 1, 0]);
+    assert_eq!(STEPPED, [0, 8, 16, 24, 32, 40, 48, 56]);
+    assert_eq!(REVERSED_STEPPED, [8, 4, 0]);
+    assert_eq!(WIDE, [i64::MAX / 2 + 2, 1, i64::MIN / 2 + 1, i64::MIN + 1]);
+
+    let registers = Registers {
+        reg0: 0,
+        reg8: 8,
+        reg16: 16,
+        reg24: 24,
+    };
+    assert_eq!(registers.reg24, 24);
+}
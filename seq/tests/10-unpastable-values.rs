@@ -0,0 +1,26 @@
+// A value that cannot be spelled as part of an identifier is reported at the
+// paste, instead of making the macro panic.
+
+use seq::seq;
+
+seq!(N in -2..2 {
+    struct S~N;
+});
+
+seq!(C in ' '..='"' {
+    struct S~C;
+});
+
+seq!(B in b'\x7f'..=b'\x80' {
+    struct S~B;
+});
+
+seq!(N in -3..-1 {
+    struct T~{N:02};
+});
+
+seq!(N in 0..2 {
+    struct U~{N - 1};
+});
+
+fn main() {}
@@ -0,0 +1,40 @@
+// Ranges can also be written over char and byte literals, which are
+// substituted as literals of the same kind and pasted into identifiers as the
+// character itself. Integer literals keep their type suffix.
+
+use seq::seq;
+
+const LOWERCASE: [char; 5] = seq!(C in 'a'..='e' { [#(C,)*] });
+
+const DIGITS: &[u8] = &seq!(B in b'0'..=b'9' { [#(B,)*] });
+
+seq!(C in 'a'..'d' {
+    fn letter_~C() -> char {
+        C
+    }
+});
+
+seq!(B in b'0'..=b'2' {
+    #[allow(dead_code)]
+    enum Digit {
+        #(
+            Digit~B = B as isize,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(LOWERCASE, ['a', 'b', 'c', 'd', 'e']);
+    assert_eq!(DIGITS, b"0123456789");
+    assert_eq!(letter_c(), 'c');
+    assert_eq!(Digit::Digit2 as u8, b'2');
+
+    assert_eq!(letter_a(), 'a');
+
+    // Calling a method on an unsuffixed literal would not type check.
+    let ones = seq!(N in 0u8..4u8 { [#(N.count_ones(),)*] });
+    assert_eq!(ones, [0, 1, 1, 2]);
+
+    let max = seq!(N in 250..=255u8 { [#(N.leading_zeros(),)*] });
+    assert_eq!(max, [0; 6]);
+}
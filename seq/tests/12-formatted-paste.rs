@@ -0,0 +1,44 @@
+// A `{N:spec}` directive formats the value before pasting it into an
+// identifier: a leading width zero pads the value, and a trailing `x`, `X`,
+// `o` or `b` selects the radix.
+
+use seq::seq;
+
+seq!(N in 0..12 {
+    #[allow(dead_code)]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Register {
+        #(
+            Reg~{N:02},
+        )*
+    }
+});
+
+seq!(N in 10..=11 {
+    const IRQ_~{N:X}~_NAME: &str = stringify!(Irq~{N:x}~Handler);
+});
+
+seq!(N in 0..4 {
+    #[allow(dead_code)]
+    struct Mask {
+        #(
+            bit~{N:04b}: bool,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(Register::Reg03 as u8, 3);
+    assert_eq!(Register::Reg11 as u8, 11);
+
+    assert_eq!(IRQ_A_NAME, "IrqaHandler");
+    assert_eq!(IRQ_B_NAME, "IrqbHandler");
+
+    let mask = Mask {
+        bit0000: true,
+        bit0001: false,
+        bit0010: false,
+        bit0011: true,
+    };
+    assert!(mask.bit0011);
+}
@@ -0,0 +1,43 @@
+// Several loop variables can be bound at once. Ranges separated by commas are
+// iterated as their cartesian product, with the first variable changing
+// slowest, while `zip(...)` iterates them in lockstep.
+//
+// A `seq!` nested inside the body is expanded after the outer one, so its
+// body may refer to the outer variables as well as its own, for example when
+// pasting both into one identifier.
+
+use seq::seq;
+
+seq!((R, C) in 0..2, 0..3 {
+    const CELL_~R~_~C: (usize, usize) = (R, C);
+});
+
+const PRODUCT: [(u8, u8); 4] = seq!((A, B) in 0..2, 0..2 { [#((A, B),)*] });
+
+const ZIPPED: [(u8, u8); 3] = seq!((A, B) in zip(0..3, (10..20).rev()) { [#((A, B),)*] });
+
+seq!(R in 0..2 {
+    seq!(C in 0..2 {
+        const NESTED_~R~_~C: usize = R * 10 + C;
+    });
+});
+
+// The repeat sections of a nested `seq!` belong to it, also when its header
+// starts with an attribute.
+seq!(N in 0..2 {
+    seq!(#[mixed_site] M in 0..2 {
+        #(
+            struct Unit~N~_~M;
+        )*
+    });
+});
+
+fn main() {
+    assert_eq!(CELL_1_2, (1, 2));
+    assert_eq!(CELL_0_1, (0, 1));
+    assert_eq!(PRODUCT, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+    assert_eq!(ZIPPED, [(0, 19), (1, 18), (2, 17)]);
+    assert_eq!(NESTED_1_0, 10);
+    assert_eq!(NESTED_1_1, 11);
+    let _ = (Unit0_0, Unit0_1, Unit1_0, Unit1_1);
+}
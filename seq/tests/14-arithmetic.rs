@@ -0,0 +1,35 @@
+// The body may compute a value from the loop variable at compile time.
+// `#{expr}` expands to an integer literal, and `~{expr}` pastes the result
+// into an identifier, optionally formatted with a spec as in `~{expr:02}`.
+//
+// Only integer arithmetic, bitwise and shift operators are supported, with
+// overflow and division by zero reported as errors.
+
+use seq::seq;
+
+const OFFSETS: [usize; 4] = seq!(N in 0..4 { [#(#{N * 4},)*] });
+
+seq!(N in 1..4 {
+    struct Registers {
+        #(
+            field~{N * 2}: u8,
+        )*
+    }
+});
+
+seq!(N in 1..4 {
+    const SHIFTED_~{N - 1}: u32 = #{1 << N};
+    const PADDED_~{N * 5:02}: u32 = #{(N + 1) % 3};
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 4, 8, 12]);
+
+    let registers = Registers { field2: 1, field4: 2, field6: 3 };
+    assert_eq!(registers.field2 + registers.field4 + registers.field6, 6);
+
+    assert_eq!(SHIFTED_0, 2);
+    assert_eq!(SHIFTED_2, 8);
+    assert_eq!(PADDED_05, 2);
+    assert_eq!(PADDED_15, 1);
+}
@@ -0,0 +1,42 @@
+// Repeat sections are found at any depth in the body, next to other tokens,
+// and a body may contain several of them. A separator between `)` and `*`, as
+// in `#( ... ),*`, is emitted between the repetitions but not after the last.
+
+use seq::seq;
+
+seq!(N in 0..3 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Channel {
+        #(Ch~N,)*
+        Off,
+    }
+
+    impl Channel {
+        fn index(self) -> Option<usize> {
+            match self {
+                #(Channel::Ch~N => Some(N),)*
+                Channel::Off => None,
+            }
+        }
+
+        fn all() -> (#(Channel),*) {
+            (#(Channel::Ch~N),*)
+        }
+    }
+
+    fn sum() -> usize {
+        let mut total = 0;
+        #(total += N;)*
+        total
+    }
+});
+
+const WEIGHTS: [u8; 3] = seq!(N in 1..=3 { [#(N * 2),*] });
+
+fn main() {
+    assert_eq!(Channel::Ch2.index(), Some(2));
+    assert_eq!(Channel::Off.index(), None);
+    assert_eq!(Channel::all(), (Channel::Ch0, Channel::Ch1, Channel::Ch2));
+    assert_eq!(sum(), 3);
+    assert_eq!(WEIGHTS, [2, 4, 6]);
+}
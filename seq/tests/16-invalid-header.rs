@@ -0,0 +1,10 @@
+// Malformed headers are reported at the offending token with a targeted
+// message, rather than a generic parse error.
+
+use seq::seq;
+
+seq!(N in 0..x {});
+
+seq!(N 0..4 {});
+
+fn main() {}
@@ -0,0 +1,8 @@
+// A range whose start is past its end would never expand anything. Counting
+// down is spelled `(start..end).rev()` instead.
+
+use seq::seq;
+
+seq!(N in 4..0 {});
+
+fn main() {}
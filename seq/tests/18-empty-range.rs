@@ -0,0 +1,10 @@
+// An empty range expands the body zero times, which is almost certainly a
+// mistake, so it is rejected rather than silently expanding to nothing.
+
+use seq::seq;
+
+seq!(N in 2..2 {
+    compile_error!("never expanded");
+});
+
+fn main() {}
@@ -0,0 +1,10 @@
+// The number of iterations is limited, so that a typo in a bound is reported
+// promptly instead of making the compiler appear to hang.
+
+use seq::seq;
+
+seq!(N in 0..10_000_000 {});
+
+seq!((A, B) in 0..1000, 0..1000 {});
+
+fn main() {}
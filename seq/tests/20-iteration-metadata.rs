@@ -0,0 +1,38 @@
+// Besides the loop variables, the body can refer to where the iteration falls
+// in the sequence: `#index` counts from zero, `#len` is the number of
+// iterations, and `#first` and `#last` are boolean literals.
+
+use seq::seq;
+
+const INDICES: [(i32, usize); 3] = seq!(N in (10..13).rev() { [#((N, #index)),*] });
+
+seq!(N in 0..4 {
+    fn describe(n: u8) -> &'static str {
+        #(
+            if n == N && #first {
+                "first"
+            } else if n == N && #last {
+                concat!("last of ", #len)
+            } else
+        )*
+        {
+            "other"
+        }
+    }
+});
+
+seq!(N in 0..3 {
+    const LEN_~N: usize = #len;
+    const IS_LAST_~N: bool = #last;
+});
+
+fn main() {
+    assert_eq!(INDICES, [(12, 0), (11, 1), (10, 2)]);
+    assert_eq!(describe(0), "first");
+    assert_eq!(describe(2), "other");
+    assert_eq!(describe(3), "last of 4");
+    assert_eq!(describe(9), "other");
+    assert_eq!(LEN_1, 3);
+    assert!(!IS_LAST_1);
+    assert!(IS_LAST_2);
+}
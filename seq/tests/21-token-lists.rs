@@ -0,0 +1,72 @@
+// Instead of a range, a loop variable can iterate over a list of arbitrary
+// tokens, such as types. Several variables can destructure a list of tuples.
+//
+// Elements made of a single identifier or literal can be pasted with `~`, and
+// integer literals can be used in arithmetic. Char and byte literals behave as
+// they do in a range, and other literals such as strings are substituted as
+// they are.
+
+use std::collections::HashMap;
+
+use seq::seq;
+
+trait Width {
+    fn width() -> usize;
+}
+
+seq!(T in [u8, u16, u32, u64] {
+    impl Width for T {
+        fn width() -> usize {
+            std::mem::size_of::<T>() * 8
+        }
+    }
+});
+
+seq!((Name, Ty) in [(Small, u8), (Map, HashMap<u8, String>), (Callback, fn(u8) -> u8)] {
+    struct Name(Ty);
+});
+
+seq!(T in [u8, i64] {
+    fn parse_~T(s: &str) -> T {
+        s.parse().unwrap()
+    }
+});
+
+seq!(T in [r#type, r#match] {
+    struct Keyword~T;
+});
+
+const SCALED: [u32; 3] = seq!(N in [1, 4, 9] { [#(#{N * 10},)*] });
+
+const NAMES: [&str; 2] = seq!(S in ["a", "b"] { [#(S,)*] });
+
+const CODES: [u32; 2] = seq!(C in ['a', b'b'] { [#(#{C + 1},)*] });
+
+seq!(C in ['X', b'Y'] {
+    const AXIS_~C: u8 = C as u8;
+});
+
+seq!((Ty, N) in [u8, u16], 1..=2 {
+    fn zero_~Ty~_~N() -> [Ty; N] {
+        [0; N]
+    }
+});
+
+fn main() {
+    assert_eq!(<u16 as Width>::width(), 16);
+    assert_eq!(<u64 as Width>::width(), 64);
+
+    let _ = Small(1);
+    let _ = Map(HashMap::new());
+    let callback = Callback(|x| x + 1);
+    assert_eq!((callback.0)(1), 2);
+
+    assert_eq!(parse_u8("7"), 7);
+    assert_eq!(parse_i64("-7"), -7);
+    let _ = (Keywordtype, Keywordmatch);
+    assert_eq!(SCALED, [10, 40, 90]);
+    assert_eq!(NAMES, ["a", "b"]);
+    assert_eq!(CODES, [98, 99]);
+    assert_eq!((AXIS_X, AXIS_Y), (b'X', b'Y'));
+    assert_eq!(zero_u16_2(), [0u16; 2]);
+}
@@ -0,0 +1,29 @@
+// Range bounds may be integer arithmetic on literals, and may arrive from a
+// `macro_rules!` metavariable such as `$end:expr`, which is passed on wrapped
+// in an invisible group.
+
+use seq::seq;
+
+const SHIFTED: [u32; 8] = seq!(N in 0..1 << 3 { [#(N,)*] });
+
+const MIXED: [i32; 3] = seq!(N in -(2 * 2)..-1 { [#(N,)*] });
+
+macro_rules! squares {
+    ($start:expr, $end:expr) => {
+        seq!(N in $start..=$end { [#(#{N * N},)*] })
+    };
+}
+
+macro_rules! literal_bound {
+    ($end:literal) => {
+        seq!(N in 0u8..$end { [#(N,)*] })
+    };
+}
+
+fn main() {
+    assert_eq!(SHIFTED, [0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(MIXED, [-4, -3, -2]);
+    let squares: [u64; 3] = squares!(1 + 1, 2 * 2);
+    assert_eq!(squares, [4, 9, 16]);
+    assert_eq!(literal_bound!(3), [0u8, 1, 2]);
+}
@@ -0,0 +1,29 @@
+// A raw identifier can be the prefix of a pasted identifier, which is then
+// raw as well, and literals such as `2` can be pasted onto an identifier even
+// when no loop variable is involved.
+
+use seq::seq;
+
+seq!(N in 0..2 {
+    struct Token~N {
+        r#type~N: u8,
+        r#match: u8,
+    }
+});
+
+seq!(N in 1..3 {
+    const LEVEL_~N~_~0: u32 = N * 10;
+});
+
+seq!(N in 0..1 {
+    struct Version~2;
+});
+
+fn main() {
+    let token = Token1 { type1: 1, r#match: 2 };
+    assert_eq!(token.type1 + token.r#match, 3);
+    let _ = Token0 { r#type0: 0, r#match: 0 };
+    assert_eq!(LEVEL_1_0, 10);
+    assert_eq!(LEVEL_2_0, 20);
+    let _ = Version2;
+}
@@ -0,0 +1,18 @@
+// With `#[mixed_site]`, pasted identifiers resolve at the macro definition
+// site, so a local introduced by the body is not visible to the caller.
+//
+// Errors about a pasted identifier point at all of the joined tokens where
+// the compiler can join spans, and at the prefix otherwise.
+
+use seq::seq;
+
+fn main() {
+    seq!(#[mixed_site] N in 0..1 {
+        let hidden~N = N;
+    });
+    let _ = hidden0;
+
+    seq!(N in 0..1 {
+        let _ = missing~N~_value;
+    });
+}
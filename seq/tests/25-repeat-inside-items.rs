@@ -0,0 +1,33 @@
+// Wrapping a whole item in `seq!` stamps out only its repeat sections, so
+// the variants of an enum or the items of a trait impl can be generated
+// without repeating the item around them.
+
+use seq::seq;
+
+trait Handler {
+    fn handle(&self, irq: u8) -> Option<u8>;
+}
+
+seq!(N in 0..16 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Interrupt {
+        #(Irq~N = N,)*
+    }
+
+    struct Device;
+
+    impl Handler for Device {
+        fn handle(&self, irq: u8) -> Option<u8> {
+            match irq {
+                #(N => Some(Interrupt::Irq~N as u8),)*
+                _ => None,
+            }
+        }
+    }
+});
+
+fn main() {
+    assert_eq!(Interrupt::Irq15 as u8, 15);
+    assert_eq!(Device.handle(3), Some(3));
+    assert_eq!(Device.handle(16), None);
+}
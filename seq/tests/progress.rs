@@ -11,4 +11,22 @@ fn tests() {
     t.pass("tests/07-inclusive-range.rs");
     t.compile_fail("tests/08-ident-span.rs");
     t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-signed-reversed-stepped.rs");
+    t.compile_fail("tests/10-oversized-step.rs");
+    t.compile_fail("tests/10-unpastable-values.rs");
+    t.pass("tests/11-char-byte-and-suffix.rs");
+    t.pass("tests/12-formatted-paste.rs");
+    t.pass("tests/13-multiple-variables.rs");
+    t.pass("tests/14-arithmetic.rs");
+    t.pass("tests/15-repeat-sections.rs");
+    t.compile_fail("tests/16-invalid-header.rs");
+    t.compile_fail("tests/17-range-start-greater-than-end.rs");
+    t.compile_fail("tests/18-empty-range.rs");
+    t.compile_fail("tests/19-too-many-iterations.rs");
+    t.pass("tests/20-iteration-metadata.rs");
+    t.pass("tests/21-token-lists.rs");
+    t.pass("tests/22-bound-expressions.rs");
+    t.pass("tests/23-raw-and-literal-paste.rs");
+    t.compile_fail("tests/24-pasted-ident-hygiene.rs");
+    t.pass("tests/25-repeat-inside-items.rs");
 }
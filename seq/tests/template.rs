@@ -0,0 +1,78 @@
+// Expanding a body through a `Template` must give the same tokens as running
+// `substitute_target` over the whole body, for every iteration. The bodies
+// here mix the tokens that start a slot (`#`, `~`, loop variables and nested
+// `seq!`) with the ones that do not, at several depths.
+//
+// Like the benchmark, this compiles the macro's internals in directly.
+
+#[path = "../src/eval.rs"]
+mod eval;
+#[allow(dead_code)]
+#[path = "../src/expand.rs"]
+mod expand;
+#[path = "../src/template.rs"]
+mod template;
+
+use proc_macro2::TokenStream;
+
+use crate::expand::{substitute_target, Bindings, Position, Sequence};
+use crate::template::Template;
+
+fn assert_same_expansion(input: &str) {
+    let sequence = syn::parse_str::<Sequence>(input).unwrap();
+    let iterations = sequence.iterations();
+    let template = Template::new(sequence.content.clone(), &sequence.idents);
+    let len = iterations.len();
+    for (index, substitutions) in iterations.iter().enumerate() {
+        let bindings = Bindings::new(&sequence.idents, substitutions, Position { index, len });
+        let substituted = substitute_target(sequence.content.clone(), &bindings).unwrap();
+        let instantiated = template.instantiate(&bindings).unwrap();
+        assert_eq!(
+            substituted.to_string(),
+            instantiated.to_string(),
+            "iteration {index} of `{input}`",
+        );
+    }
+}
+
+#[test]
+fn hash_before_paste() {
+    assert_same_expansion("N in 0..2 { stringify!(#Foo~N) }");
+    assert_same_expansion("N in 0..2 { # Foo~N~_~{N:02} #[doc = \"x\"] #! }");
+}
+
+#[test]
+fn metadata_and_arithmetic() {
+    assert_same_expansion("N in 0..3 { [#index, #len, #first, #last, #other, #{N * 2}] }");
+    assert_same_expansion("N in 0..3 { (#(N),*) { #{N + 1} #index~N } }");
+}
+
+#[test]
+fn pastes_in_groups() {
+    assert_same_expansion(
+        "(A, B) in 0..2, [u8, r#type] {
+            struct S~A~B { field~{A:02}: [B; #{A + 1}], r#type~A: (), Tail~2: () }
+            impl S~A~B { fn f(&self) -> usize { let a~b~A = A; a~b~A + { A } } }
+        }",
+    );
+}
+
+#[test]
+fn nested_sequence() {
+    assert_same_expansion("N in 0..2 { seq!(M in 0..2 { const C~N~_~M: usize = #{N + M}; }); }");
+    assert_same_expansion("N in 0..2 { { seq!(#[mixed_site] M in 0..2 { #(A~N~M)* }); N } }");
+}
+
+#[test]
+fn plain_tokens() {
+    let sequence = syn::parse_str::<Sequence>("N in 0..1 { a ~ b; # [c] }").unwrap();
+    let template = Template::new(sequence.content.clone(), &sequence.idents);
+    let substitutions = &sequence.iterations()[0];
+    let bindings = Bindings::new(
+        &sequence.idents,
+        substitutions,
+        Position { index: 0, len: 1 },
+    );
+    let instantiated: TokenStream = template.instantiate(&bindings).unwrap();
+    assert_eq!(instantiated.to_string(), sequence.content.to_string());
+}